@@ -16,6 +16,7 @@
         &std::fs::read("data/tiger.svg").unwrap()).unwrap();
 
     group.bench_function("tiny_skia", |b| b.iter(|| tvg.render(1.0)));
+    group.bench_function("grays", |b| b.iter(|| intvg::render_grays::Render::render(&tvg, 1.0)));
     #[cfg(feature = "evg")] group.bench_function("GPAC/EVG",
         |b| b.iter(|| intvg::render_evg::Render::render(&tvg, 1.0)));
     #[cfg(feature = "b2d")] group.bench_function("Blend2D",
@@ -24,6 +25,43 @@
     group.finish();
  }
 
- criterion_group!(benches, bench_engine_2d);
+ fn bench_threads(c: &mut Criterion) {
+    use intvg::{tinyvg::TVGImage, render::*, convert::Convert};
+    let mut group = c.benchmark_group("threads");
+    group.sample_size(10);
+
+    let tvg = TVGImage::from_usvg(
+        &std::fs::read("data/tiger.svg").unwrap()).unwrap();
+
+    for threads in [1, 2, 4, 8] {  // rendering at 4x scale in parallel bands
+        let opts = RenderOptions { scale: 4.0, threads, ..Default::default() };
+        group.bench_function(format!("tiny_skia/{threads}"),
+            |b| b.iter(|| tvg.render_with(&opts)));
+        #[cfg(feature = "b2d")] group.bench_function(format!("Blend2D/{threads}"),
+            |b| b.iter(|| intvg::render_b2d::Render::render_with(&tvg, &opts)));
+    }
+
+    group.finish();
+ }
+
+ fn bench_prepared(c: &mut Criterion) {
+    use intvg::{tinyvg::TVGImage, render::*};
+    let mut group = c.benchmark_group("prepared");
+    group.sample_size(10);
+
+    let tvg = TVGImage::load_data(&mut std::io::BufReader::new(
+        std::fs::File::open("data/tiger.tvg").unwrap())).unwrap();
+    let scene = PreparedScene::new(&tvg, &RenderOptions::default()).unwrap();
+
+    for scale in [0.25, 1.0] {  // like on resizing, path building matters more at small sizes
+        let opts = RenderOptions { scale, ..Default::default() };
+        group.bench_function(format!("render/{scale}"), |b| b.iter(|| tvg.render_with(&opts)));
+        group.bench_function(format!("scene/{scale}"), |b| b.iter(|| scene.render_with(&opts)));
+    }
+
+    group.finish();
+ }
+
+ criterion_group!(benches, bench_engine_2d, bench_threads, bench_prepared);
  criterion_main! (benches);
 
@@ -1,5 +1,8 @@
 
-use std::{env, path::{Path, PathBuf}, process::Command};
+use std::{path::PathBuf, process::Command};
+#[cfg(any(feature = "b2d", feature = "evg", feature = "ftg", feature = "ovg", feature = "ugl"))]
+use std::path::Path;
+#[cfg(any(feature = "b2d", feature = "ovg"))] use std::env;
 
 //  https://doc.rust-lang.org/stable/cargo/reference/build-scripts.html
 fn main() -> Result<(), Box<dyn std::error::Error>> {
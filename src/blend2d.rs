@@ -64,6 +64,13 @@ impl BLContext { //  https://blend2d.com/doc/group__bl__rendering.html
         bl_result!(bl_context_init_as(&mut ctx, &mut img.0, null()))?;
         Ok(Self(ctx, Some(img)))
     }
+    /// Render asynchronously on `threads` worker threads, or synchronously if 0.
+    pub fn with_threads(mut img: BLImage, threads: u32) -> Result<Self, BLErr> {
+        let (mut ctx, mut info) = (object_init(), object_init::<BLContextCreateInfo>());
+        info.thread_count = threads;
+        bl_result!(bl_context_init_as(&mut ctx, &mut img.0, &info))?;
+        Ok(Self(ctx, Some(img)))
+    }
     pub fn get_target_image(&self) -> &BLImage {
         self.1.as_ref().expect("BLContext always owns its target image")
     }
@@ -1300,7 +1307,7 @@ impl_style!(BLSolidColor);
 #[derive(Debug)] pub struct BLErr(BLResult);
 impl BLErr {
     pub fn code(&self) -> BLResult { self.0 }
-    fn invalid_value() -> Self { Self(BLResultCode::BL_ERROR_INVALID_VALUE as _) }
+    pub(crate) fn invalid_value() -> Self { Self(BLResultCode::BL_ERROR_INVALID_VALUE as _) }
 }
 impl core::fmt::Display for BLErr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -0,0 +1,162 @@
+
+use crate::tinyvg::*;
+use std::io;
+
+/// **Canvas**: a 2D rendering backend with a current path, filled or stroked with paints
+/// converted from TinyVG styles. `draw_command` traverses the commands the same way
+/// for every engine, which just implements the primitives.
+pub trait Canvas {
+    type Paint;     type Error;
+
+    /// Convert a `style` of `img` into a paint, e.g. a gradient shader.
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error>;
+
+    fn move_to (&mut self, pt: Point);
+    fn line_to (&mut self, pt: Point);
+    fn quad_to (&mut self, ctrl: Point, end: Point);
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point);
+    /// Elliptical arc like SVG from the current point `start` to `end`, `rotation` in degrees.
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error>;
+    fn close(&mut self);
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error>;
+
+    /// Fill the current path by the non-zero winding rule.
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error>;
+    /// Stroke the current path `width` wide in image units with round joins and caps,
+    /// kept at least a device pixel wide by the backend.
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error>;
+    /// Discard the current path.
+    fn clear (&mut self);
+
+    /// Draw a command of `img`, leaving the current path empty.
+    fn draw_command<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        cmd: &Command) -> Result<(), Self::Error> where Self: Sized {
+        match cmd {     Command::EndOfDocument => (),
+            Command::FillPolyg(FillCMD { fill, coll }) => {
+                let paint = self.paint(img, fill)?;
+                polyline(self, coll);   self.close();   self.fill(&paint)?;
+            }
+            Command::FillRects(FillCMD { fill, coll }) => {
+                let paint = self.paint(img, fill)?;
+                for rect in coll { self.rect(rect)? }   self.fill(&paint)?;
+            }
+            Command::FillPath (FillCMD { fill, coll }) => {
+                let paint = self.paint(img, fill)?;
+                for seg in coll { segment_to_path(self, seg)?; }    self.fill(&paint)?;
+            }
+            Command::DrawLines(DrawCMD { line, lwidth, coll }) => {
+                let paint = self.paint(img, line)?;
+                for line in coll { self.move_to(line.start);    self.line_to(line.end); }
+                self.stroke(&paint, *lwidth)?;
+            }
+            Command::DrawLoop (DrawCMD { line, lwidth, coll },
+                strip) => {     let paint = self.paint(img, line)?;
+                polyline(self, coll);   if !*strip { self.close() }
+                self.stroke(&paint, *lwidth)?;
+            }
+            Command::DrawPath (DrawCMD {
+                line, lwidth, coll }) => {
+                let (paint, mut lwidth) = (self.paint(img, line)?, *lwidth);
+                for seg in coll { stroke_segment_path(self, seg, &paint, &mut lwidth)? }
+            }
+            Command::OutlinePolyg(fill, DrawCMD {
+                line, lwidth, coll }) => {
+                let (paint, pline) = (self.paint(img, fill)?, self.paint(img, line)?);
+                polyline(self, coll);   self.close();
+                self.fill(&paint)?;     self.stroke(&pline, *lwidth)?;
+            }
+            Command::OutlineRects(fill, DrawCMD {
+                line, lwidth, coll }) => {
+                let (paint, pline) = (self.paint(img, fill)?, self.paint(img, line)?);
+                for rect in coll { self.rect(rect)? }
+                self.fill(&paint)?;     self.stroke(&pline, *lwidth)?;
+            }
+            Command::OutlinePath (fill, DrawCMD {
+                line, lwidth, coll }) => {
+                let (paint, pline) = (self.paint(img, fill)?, self.paint(img, line)?);
+                let mut res = false;
+                for seg in coll { res |= segment_to_path(self, seg)?; }
+                self.fill(&paint)?;
+
+                if res {    self.clear();   let mut lwidth = *lwidth;
+                    for seg in coll { stroke_segment_path(self, seg, &pline, &mut lwidth)? }
+                } else { self.stroke(&pline, *lwidth)?; }
+            }
+        }   self.clear();   Ok(())
+    }
+}
+
+fn polyline<C: Canvas>(canvas: &mut C, coll: &[Point]) {
+    let mut iter = coll.iter();
+    if let Some(&pt) = iter.next() { canvas.move_to(pt) }
+    iter.for_each(|&pt| canvas.line_to(pt));
+}
+
+/// Stroke a segment split into paths of the same line width, starting with `lwidth`,
+/// which follows the line width changes.
+fn stroke_segment_path<C: Canvas>(canvas: &mut C, seg: &Segment,
+    paint: &C::Paint, lwidth: &mut f32) -> Result<(), C::Error> {
+    let (mut last, mut drawn, mut split) = (seg.start, false, false);
+    canvas.move_to(seg.start);
+
+    for cmd in &seg.cmds {
+        if let Some(width) = cmd.lwidth {
+            if drawn {  canvas.stroke(paint, *lwidth)?;
+                canvas.clear();     canvas.move_to(last);   (drawn, split) = (false, true);
+            }   *lwidth = width;
+        }
+        // closing a split path is a line back to the start of the segment
+        if split && matches!(cmd.instr, SegInstr::ClosePath) {
+            canvas.line_to(seg.start);  last = seg.start;   drawn = true;   continue
+        }   process_segcmd(canvas, &cmd.instr, &mut last)?;
+        drawn |= !matches!(cmd.instr, SegInstr::ClosePath);
+    }   canvas.stroke(paint, *lwidth)?;     canvas.clear();     Ok(())
+}
+
+/// Append a segment to the current path, returns whether it changes the line width.
+pub fn segment_to_path<C: Canvas>(canvas: &mut C, seg: &Segment) -> Result<bool, C::Error> {
+    let (mut last, mut change_lw) = (seg.start, false);     canvas.move_to(seg.start);
+
+    for cmd in &seg.cmds {
+        if cmd.lwidth.is_some() { change_lw = true }
+        process_segcmd(canvas, &cmd.instr, &mut last)?;
+    }   Ok(change_lw)
+}
+
+/// Append an instruction to the current path, following the current point `last`.
+pub fn process_segcmd<C: Canvas>(canvas: &mut C, instr: &SegInstr,
+    last: &mut Point) -> Result<(), C::Error> {
+    let end = match instr {     SegInstr::ClosePath => { canvas.close();   return Ok(()) }
+        SegInstr::Line  { end } => { canvas.line_to(*end);  *end }
+        SegInstr::HLine { x } => {
+            let end = Point { x: *x, y: last.y };   canvas.line_to(end);    end
+        }
+        SegInstr::VLine { y } => {
+            let end = Point { x: last.x, y: *y };   canvas.line_to(end);    end
+        }
+
+        SegInstr::CubicBezier { ctrl, end } => { canvas.cubic_to(*ctrl, *end);  *end }
+        // the sweep flag of TinyVG is the inverse of SVG's
+        SegInstr::ArcCircle  { large, sweep, radius, end } => {
+            canvas.arc_to(*last, (*radius, *radius), 0.0, *large, !*sweep, *end)?;  *end
+        }
+        SegInstr::ArcEllipse { large, sweep, radii, rotation, end } => {
+            canvas.arc_to(*last, *radii, *rotation, *large, !*sweep, *end)?;    *end
+        }
+        SegInstr::QuadBezier { ctrl, end } => { canvas.quad_to(*ctrl, *end);    *end }
+    };  *last = end;    Ok(())
+}
+
+/// The SVG arc from `start` to `end` in kurbo, e.g. to approximate by cubic Béziers
+/// for backends without elliptical arcs.
+pub fn svg_arc(start: Point, radii: (f32, f32), rotation: f32,
+    large: bool, sweep: bool, end: Point) -> kurbo::SvgArc {
+    kurbo::SvgArc {
+           to: kurbo::Point::new(end.x as _, end.y as _),
+         from: kurbo::Point::new(start.x as _, start.y as _),
+        radii: kurbo::Vec2 ::new(radii.0 as _, radii.1 as _),
+        x_rotation: (rotation as f64).to_radians(), large_arc: large, sweep,
+    }
+}
@@ -0,0 +1,312 @@
+
+//! Code generation of TinyVG into draw routines without a runtime parser: JavaScript on
+//! Canvas2D (as `wcnvs` renders), Rust on tiny-skia `PathBuilder`, or C on a small path API.
+
+use crate::{tinyvg::*, canvas::{Canvas, svg_arc}, render::gradient_stops};
+use std::{convert::Infallible, io};
+
+/// Target language of the generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] pub enum Lang { JavaScript, Rust, C }
+
+impl Lang {
+    /// By the extension of a source file: `js`, `rs` or `c`.
+    pub fn from_ext(ext: &str) -> Option<Self> {
+        match ext { "js" => Some(Self::JavaScript), "rs" => Some(Self::Rust),
+            "c" => Some(Self::C), _ => None }
+    }
+}
+
+/// An identifier after `name`, e.g. a file stem: other than ASCII alphanumerics into `_`,
+/// and prefixed by `tvg_` if starting with a digit.
+pub fn identifier(name: &str) -> String {
+    let ident = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("tvg_{ident}") } else { ident }
+}
+
+pub trait CodeGen {
+    /// Write the size and color table as constants prefixed by `name` in upper case,
+    /// and a routine `draw_{name}` drawing the image the same as rendered by `Render`.
+    /// Fails if `name` isn't an identifier, see `identifier`.
+    fn save_code(&self, lang: Lang, name: &str, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl<R: io::Read, W: io::Write> CodeGen for TinyVG<R, W> {
+    fn save_code(&self, lang: Lang, name: &str, writer: &mut dyn io::Write) -> io::Result<()> {
+        if identifier(name) != name { return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            format!("not an identifier: {name}"))) }
+        let (width, height) = (self.header.width, self.header.height);
+        let (upper, count) = (name.to_uppercase(), self.color_table.len());
+        let mut canvas = CodeCanvas { lang, code: String::new(), line: String::new(),
+            paints: 0, dirty: false };
+        for (idx, cmd) in self.commands.iter().enumerate() {
+            if matches!(cmd, Command::EndOfDocument) { continue }
+            canvas.paints = 0;  canvas.code.push_str(&format!("    {{  // #{idx}\n"));
+            let _ = canvas.draw_command(self, cmd);     canvas.code.push_str("    }\n");
+        }
+
+        let comment = if lang == Lang::C { ("/*", " */") } else { ("//", "") };
+        writeln!(writer, "{} Generated by {} {} from a TinyVG image of {width}x{height}, \
+            do not edit.{}\n", comment.0, env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"),
+            comment.1)?;
+        let code = &canvas.code;    match lang {
+            Lang::JavaScript => writeln!(writer, "const {upper}_SIZE = [{width}, {height}];\n\
+                const {upper}_COLORS = [\n{}];\n\n\
+                /** Draw into the Canvas2D `ctx` by its transform from image to pixels. */\n\
+                function draw_{name}(ctx) {{\n    \
+                const C = {upper}_COLORS, m = ctx.getTransform();\n{JS_PRELUDE}{code}}}",
+                wrap(self.color_table.iter().map(|c| format!("\"{}\",", css(*c))))),
+
+            Lang::Rust => {     // just the helpers in use, free of warnings
+                let prelude = RS_PRELUDE.iter().filter(|(uses, _)| uses.is_empty() ||
+                    uses.iter().any(|name| code.contains(name))).map(|(_, text)| &text[1..])
+                    .collect::<String>();
+                writeln!(writer, "pub const {upper}_SIZE: (u32, u32) = ({width}, {height});\n\
+                    pub const {upper}_COLORS: [[u8; 4]; {count}] = [\n{}];\n\n\
+                    /// Draw into `pixmap` by `trfm` from image to pixel coordinates.\n\
+                    pub fn draw_{name}(pixmap: &mut tiny_skia::PixmapMut, \
+                    trfm: tiny_skia::Transform) {{\n    use tiny_skia as skia;\n    \
+                    const C: [[u8; 4]; {count}] = {upper}_COLORS;\n{prelude}{code}}}",
+                    wrap(self.color_table.iter().map(|c|
+                        format!("[{}, {}, {}, {}],", c.r, c.g, c.b, c.a))))
+            }
+
+            Lang::C => writeln!(writer, "{C_API}\n#define {upper}_WIDTH  {width}\n\
+                #define {upper}_HEIGHT {height}\n\
+                static const uint32_t {upper}_COLORS[{count}] = {{\n{}}};\n\n\
+                /* Draw into `ctx` of the path API in image coordinates. */\n\
+                void draw_{name}(void *ctx) {{\n    \
+                const uint32_t *C = {upper}_COLORS;     tvg_clear(ctx);\n{code}}}",
+                wrap(self.color_table.iter().map(|c|
+                    format!("0x{:02x}{:02x}{:02x}{:02x},", c.r, c.g, c.b, c.a)))),
+        }
+    }
+}
+
+/// Thinnest line width of a device pixel by the transform, as `render::min_line_width`.
+const JS_PRELUDE: &str = r#"    const det = m.a * m.d - m.b * m.c;
+    const sum = m.a * m.a + m.b * m.b + m.c * m.c + m.d * m.d;
+    const smin = Math.sqrt((sum - Math.sqrt(Math.max(sum * sum - 4 * det * det, 0))) / 2);
+    const hairline = 0 < smin ? 1 / smin : 0;
+    ctx.lineCap = "round";  ctx.lineJoin = "round";     ctx.beginPath();
+"#;
+
+/// Helpers of the Rust routine (after a newline), each emitted if any of its users
+/// is in the code.
+const RS_PRELUDE: &[(&[&str], &str)] = &[
+    (&["stroke."], r#"
+    let det = trfm.sx * trfm.sy - trfm.kx * trfm.ky;
+    let sum = trfm.sx * trfm.sx + trfm.kx * trfm.kx + trfm.ky * trfm.ky + trfm.sy * trfm.sy;
+    let smin = ((sum - (sum * sum - 4.0 * det * det).max(0.0).sqrt()) / 2.0).sqrt();
+    let hairline = if 0.0 < smin { 1.0 / smin } else { 0.0 };  // lines of a pixel at least
+"#),
+    (&["flat(", "stop("], r#"
+    let color = |c: [u8; 4]| skia::Color::from_rgba8(c[0], c[1], c[2], c[3]);
+"#),
+    (&["flat("], r#"
+    let flat  = |c: [u8; 4]| { let mut paint = skia::Paint::default();
+        paint.set_color(color(c));  paint };
+"#),
+    (&["gradient("], r#"
+    let gradient = |shader: Option<skia::Shader<'static>>| skia::Paint { shader:
+        shader.unwrap_or(skia::Shader::SolidColor(skia::Color::TRANSPARENT)),
+        ..Default::default() };
+    let stop = |pos, c| skia::GradientStop::new(pos, color(c));
+"#),
+    (&["rect("], r#"
+    let rect = |pb: &mut skia::PathBuilder, x, y, w, h|
+        if let Some(rect) = skia::Rect::from_xywh(x, y, w, h) { pb.push_rect(rect) };
+"#),
+    (&["stroke."], r#"
+    let mut stroke = skia::Stroke { line_join: skia::LineJoin::Round,
+        line_cap: skia::LineCap::Round, ..Default::default() };
+"#),
+    (&[], "\n    let mut pb = skia::PathBuilder::new();\n"),
+];
+
+/// The small path API in C, declared once for all generated routines.
+const C_API: &str = r#"#include <stddef.h>
+#include <stdint.h>
+
+#ifndef TVG_PATH_API
+#define TVG_PATH_API
+/* The small path API to implement on the target: strokes with round caps and joins,
+   of a device pixel wide at least, paints of colors as 0xRRGGBBAA, gradients through
+   the stops at offsets, padded with the end colors. */
+typedef struct tvg_paint {
+    enum { TVG_FLAT, TVG_LINEAR, TVG_RADIAL } kind;
+    float x0, y0, x1, y1;   /* start and end points of gradients */
+    unsigned nstops;    const float *offsets;   const uint32_t *colors;
+} tvg_paint;
+
+void tvg_move_to (void *ctx, float x, float y);
+void tvg_line_to (void *ctx, float x, float y);
+void tvg_quad_to (void *ctx, float cx, float cy, float x, float y);
+void tvg_cubic_to(void *ctx, float cx0, float cy0, float cx1, float cy1, float x, float y);
+void tvg_close (void *ctx);
+void tvg_fill  (void *ctx, const tvg_paint *paint);
+void tvg_stroke(void *ctx, const tvg_paint *paint, float width);
+void tvg_clear (void *ctx);     /* discard the current path */
+#endif
+"#;
+
+/// Code of the commands in blocks, the path operations packed in `line`,
+/// and whether the current path is not empty yet (`dirty`).
+struct CodeCanvas { lang: Lang, code: String, line: String, paints: usize, dirty: bool }
+
+impl CodeCanvas {
+    /// Append a statement, packed into lines of at most 99 columns.
+    fn stmt(&mut self, stmt: &str) {
+        if !self.line.is_empty() && 99 < 8 + self.line.len() + 1 + stmt.len() { self.flush() }
+        if !self.line.is_empty() { self.line.push(' ') }    self.line.push_str(stmt);
+    }
+
+    fn flush(&mut self) {
+        if self.line.is_empty() { return }
+        self.code.push_str(&format!("        {}\n", self.line));   self.line.clear();
+    }
+
+    /// Call of the path operation `op` (named in snake case) with `args`.
+    fn path_op(&mut self, op: &str, args: &[f32]) {
+        let args = args.iter().map(|v| format!("{v:?}")).collect::<Vec<_>>().join(", ");
+        let stmt = match self.lang {
+            Lang::JavaScript => format!("ctx.{}({args});", match op {
+                "move_to" => "moveTo", "line_to" => "lineTo", "quad_to" => "quadraticCurveTo",
+                "cubic_to" => "bezierCurveTo", "close" => "closePath", _ => op }),
+            Lang::Rust if op == "rect" => format!("rect(&mut pb, {args});"),
+            Lang::Rust => format!("pb.{op}({args});"),
+            Lang::C if args.is_empty() => format!("tvg_{op}(ctx);"),
+            Lang::C => format!("tvg_{op}(ctx, {args});"),
+        };  self.stmt(&stmt);   self.dirty = true;
+    }
+
+    /// Declare the paint with the gradient `kind` (2 of linear, 3 of radial) through `points`.
+    fn gradient(&mut self, kind: u8, points: [f32; 5], stops: &[(f32, RGBA8888)]) -> String {
+        let (var, pts) = (format!("p{}", self.paints), points.map(|v| format!("{v:?}")));
+        self.paints += 1;
+        let decl = match self.lang {
+            Lang::JavaScript => format!("const {var} = ctx.{};\n        [{}]\n            \
+                .forEach(([pos, color]) => {var}.addColorStop(pos, color));",
+                if kind == 2 { format!("createLinearGradient({})", pts[..4].join(", "))
+                } else { format!("createRadialGradient({0}, {1}, 0, {0}, {1}, {2})",
+                    pts[0], pts[1], pts[4]) },
+                wrap_in(stops.iter().map(|(pos, c)| format!("[{pos:?}, \"{}\"],", css(*c))), 12)
+                    .trim()),
+            Lang::Rust => format!("let {var} = gradient(skia::{}, vec![\n{}            ], \
+                skia::SpreadMode::Pad, skia::Transform::identity()));",
+                if kind == 2 { format!("LinearGradient::new(({}, {}).into(),\n            \
+                    ({}, {}).into()", pts[0], pts[1], pts[2], pts[3])
+                } else { format!("RadialGradient::new(({0}, {1}).into(), 0.0,\n            \
+                    ({0}, {1}).into(), {2}", pts[0], pts[1], pts[4]) },
+                wrap_in(stops.iter().map(|(pos, c)|
+                    format!("stop({pos:?}, [{}, {}, {}, {}]),", c.r, c.g, c.b, c.a)), 12)),
+            Lang::C => format!("static const float o{var}[] = {{\n{}        }};\n        \
+                static const uint32_t c{var}[] = {{\n{}        }};\n        \
+                const tvg_paint {var} = {{ {}, {}, {}, {}, {}, {}, o{var}, c{var} }};",
+                wrap_in(stops.iter().map(|(pos, _)| format!("{pos:?},")), 12),
+                wrap_in(stops.iter().map(|(_, c)|
+                    format!("0x{:02x}{:02x}{:02x}{:02x},", c.r, c.g, c.b, c.a)), 12),
+                if kind == 2 { "TVG_LINEAR" } else { "TVG_RADIAL" },
+                pts[0], pts[1], pts[2], pts[3], stops.len()),
+        };  self.code.push_str(&format!("        {decl}\n"));     var
+    }
+}
+
+impl Canvas for CodeCanvas {
+    type Paint = String;    type Error = Infallible;
+
+    /// Declare the paint of `style` in the block of the command, returns its name.
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        let stops = |cindex: &(u32, u32)|  // interpolated in linear light as rendered
+            gradient_stops(img.lookup_color(cindex.0), img.lookup_color(cindex.1), false);
+
+        self.flush();   Ok(match style {
+            Style::FlatColor(idx) => {  let var = format!("p{}", self.paints);
+                self.paints += 1;   self.code.push_str(&match self.lang {
+                    Lang::JavaScript => format!("        const {var} = C[{idx}];\n"),
+                    Lang::Rust => format!("        let {var} = flat(C[{idx}]);\n"),
+                    Lang::C => format!("        const tvg_paint {var} = \
+                        {{ TVG_FLAT, 0, 0, 0, 0, 1, NULL, &C[{idx}] }};\n"),
+                }); var
+            }
+            Style::LinearGradient { points: (p0, p1), cindex } =>
+                self.gradient(2, [p0.x, p0.y, p1.x, p1.y, 0.0], &stops(cindex)),
+            Style::RadialGradient { points: (p0, p1), cindex } => self.gradient(3,
+                [p0.x, p0.y, p1.x, p1.y, (p1.x - p0.x).hypot(p1.y - p0.y)], &stops(cindex)),
+        })
+    }
+
+    fn move_to (&mut self, pt: Point) { self.path_op("move_to", &[pt.x, pt.y]) }
+    fn line_to (&mut self, pt: Point) { self.path_op("line_to", &[pt.x, pt.y]) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) {
+        self.path_op("quad_to", &[ctrl.x, ctrl.y, end.x, end.y])
+    }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.path_op("cubic_to", &[ctrl.0.x, ctrl.0.y, ctrl.1.x, ctrl.1.y, end.x, end.y])
+    }
+    /// Arcs into cubic Béziers of the same tolerance as `Render`.
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        match kurbo::Arc::from_svg_arc(&svg_arc(start, radii, rotation, large, sweep, end)) {
+            None => self.line_to(end),
+            Some(arc) => arc.to_cubic_beziers(0.1, |p1, p2, p| self.path_op("cubic_to",
+                &[p1.x as _, p1.y as _, p2.x as _, p2.y as _, p.x as _, p.y as _])),
+        }   Ok(())
+    }
+    fn close(&mut self) { self.path_op("close", &[]) }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        if self.lang != Lang::C {
+            self.path_op("rect", &[rect.x, rect.y, rect.w, rect.h]);    return Ok(())
+        }   // clockwise as in Canvas2D and tiny-skia
+        let (x1, y1) = (rect.x + rect.w, rect.y + rect.h);
+        self.path_op("move_to", &[rect.x, rect.y]);     self.path_op("line_to", &[x1, rect.y]);
+        self.path_op("line_to", &[x1, y1]);     self.path_op("line_to", &[rect.x, y1]);
+        self.close();   Ok(())
+    }
+
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.flush();   self.stmt(&match self.lang {
+            Lang::JavaScript => format!("ctx.fillStyle = {paint};   ctx.fill();"),
+            Lang::Rust => format!("if let Some(path) = pb.clone().finish() {{\n            \
+                pixmap.fill_path(&path, &{paint}, skia::FillRule::Winding, trfm, None) }}"),
+            Lang::C => format!("tvg_fill(ctx, &{paint});"),
+        }); self.flush();   Ok(())
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.flush();   match self.lang {
+            Lang::JavaScript => self.stmt(&format!("ctx.strokeStyle = {paint};   \
+                ctx.lineWidth = Math.max({width:?}, hairline);  ctx.stroke();")),
+            Lang::Rust => {     self.stmt(&format!("stroke.width = hairline.max({width:?});"));
+                self.flush();   self.stmt(&format!("if let Some(path) = pb.clone().finish() \
+                    {{\n            pixmap.stroke_path(&path, &{paint}, &stroke, trfm, None) }}"));
+            }
+            Lang::C => self.stmt(&format!("tvg_stroke(ctx, &{paint}, {width:?});")),
+        }   self.flush();   Ok(())
+    }
+    fn clear (&mut self) {
+        if !self.dirty { return }   self.stmt(match self.lang {
+            Lang::JavaScript => "ctx.beginPath();", Lang::Rust => "pb.clear();",
+            Lang::C => "tvg_clear(ctx);",
+        }); self.flush();   self.dirty = false;
+    }
+}
+
+/// CSS color of `#rrggbb`, with alpha if not opaque.
+fn css(c: RGBA8888) -> String {
+    let rgb = format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b);
+    if c.a == 255 { rgb } else { format!("{rgb}{:02x}", c.a) }
+}
+
+/// Items in lines of at most 99 columns, indented by 4.
+fn wrap(items: impl Iterator<Item = String>) -> String { wrap_in(items, 4) }
+
+fn wrap_in(items: impl Iterator<Item = String>, indent: usize) -> String {
+    let (mut text, mut line) = (String::new(), String::new());
+    for item in items {
+        if !line.is_empty() && 99 < indent + line.len() + 1 + item.len() {
+            text.push_str(&format!("{:indent$}{line}\n", ""));  line.clear();
+        }
+        if !line.is_empty() { line.push(' ') }  line.push_str(&item);
+    }   if !line.is_empty() { text.push_str(&format!("{:indent$}{line}\n", "")) }   text
+}
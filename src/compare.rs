@@ -0,0 +1,153 @@
+
+//! Image comparison of renderings, e.g. of different backends or against reference PNGs:
+//! max. difference, PSNR and SSIM, and diff images showing where they differ.
+
+use crate::{tinyvg::TinyVG, render::{Render, RenderOptions}};
+#[cfg(feature = "fidelity")] use crate::{tinyvg::TVGBuf, convert::{Convert, Lost, parse_svg}};
+use tiny_skia as skia;
+use std::io;
+
+/// Differences between two images of the same size, in premultiplied RGBA.
+#[derive(Debug, Clone, Copy, PartialEq, Default)] pub struct ImageDiff {
+    /// Max. difference of any channel, and the number of pixels differing at all.
+    pub max: u8,    pub pixels: usize,
+    /// Peak signal-to-noise ratio in dB over all channels, infinite if identical.
+    pub psnr: f64,
+    /// Mean structural similarity of the luma (over white) in 8×8 windows, 1 if identical.
+    pub ssim: f64,
+}
+
+impl ImageDiff {
+    /// Whether it's at least as similar as `psnr` and `ssim`.
+    pub fn within(&self, psnr: f64, ssim: f64) -> bool { psnr <= self.psnr && ssim <= self.ssim }
+}
+
+impl std::fmt::Display for ImageDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "max {:3}, {:7} pixels, PSNR {:6.2} dB, SSIM {:.4}",
+            self.max, self.pixels, self.psnr, self.ssim)
+    }
+}
+
+/// Compare the images `a` and `b`, which must be of the same size.
+pub fn compare(a: &skia::Pixmap, b: &skia::Pixmap) -> Result<ImageDiff, &'static str> {
+    if (a.width(), a.height()) != (b.width(), b.height()) { return Err("Different image sizes") }
+    let (mut diff, mut sse) = (ImageDiff::default(), 0u64);
+
+    for (pa, pb) in a.data().chunks_exact(4).zip(b.data().chunks_exact(4)) {
+        if pa == pb { continue }    diff.pixels += 1;
+        for (ca, cb) in pa.iter().zip(pb) {     let d = ca.abs_diff(*cb);
+            diff.max = diff.max.max(d);     sse += d as u64 * d as u64;
+        }
+    }
+
+    let mse = sse as f64 / a.data().len().max(1) as f64;
+    diff.psnr = if 0.0 < mse { 10.0 * (255.0 * 255.0 / mse).log10() } else { f64::INFINITY };
+    diff.ssim = ssim(&luma(a), &luma(b), a.width() as _, a.height() as _);  Ok(diff)
+}
+
+/// Diff image of `a` and `b` of the same size: a faded gray `a` where they're the same,
+/// and red where they differ, the more the stronger, visible even if differing by just 1.
+pub fn diff_image(a: &skia::Pixmap, b: &skia::Pixmap) -> Result<skia::Pixmap, &'static str> {
+    if (a.width(), a.height()) != (b.width(), b.height()) { return Err("Different image sizes") }
+    let mut img = skia::Pixmap::new(a.width(), a.height()).ok_or("Fail to create pixmap")?;
+
+    for ((pixel, (pa, pb)), y) in img.pixels_mut().iter_mut().zip(a.data().chunks_exact(4)
+        .zip(b.data().chunks_exact(4))).zip(luma(a)) {
+        let d = pa.iter().zip(pb).map(|(ca, cb)| ca.abs_diff(*cb)).max().unwrap_or(0);
+        let gray = 192 + (y / 4.0) as u8;   // 192..255
+        *pixel = if d == 0 { skia::PremultipliedColorU8::from_rgba(gray, gray, gray, 255)
+        } else {    let v = 160u8.saturating_sub(d.saturating_mul(4));
+            skia::PremultipliedColorU8::from_rgba(255, v, v, 255)
+        }.unwrap();
+    }   Ok(img)
+}
+
+/// Render `img` by every backend built in, converted into premultiplied RGBA pixmaps.
+pub fn render_backends<R: io::Read, W: io::Write>(img: &TinyVG<R, W>, opts: &RenderOptions)
+    -> Vec<(&'static str, Result<skia::Pixmap, String>)> {
+    #[allow(unused_mut)] let mut images = vec![
+        ("tiny-skia", img.render_with(opts).map_err(String::from)),
+        ("grays", crate::render_grays::Render::render_with(img, opts).map_err(String::from)),
+    ];
+
+    #[cfg(feature = "b2d")] images.push(("Blend2D",
+        crate::render_b2d::Render::render_with(img, opts).map_err(|e| format!("{e:?}"))
+            .and_then(|img| {   // 0xAARRGGBB premultiplied
+                let (width, stride) = (img.width() as usize, img.stride() as usize);
+                let data = img.pixels().ok_or("No pixels")?.chunks(stride).flat_map(|row|
+                    row[..width * 4].chunks_exact(4).flat_map(|px| [px[2], px[1], px[0], px[3]]))
+                    .collect();
+                skia::IntSize::from_wh(img.width(), img.height()).and_then(|size|
+                    skia::Pixmap::from_vec(data, size)).ok_or("Invalid image".into())
+            })));
+    #[cfg(feature = "evg")] images.push(("GPAC/EVG",
+        crate::render_evg::Render::render_with(img, opts).map_err(|e| format!("{e:?}"))
+            .and_then(|img| {   // straight alpha RGBA
+                let data = img.data().chunks_exact(4).flat_map(|px| {
+                    let c = skia::ColorU8::from_rgba(px[0], px[1], px[2], px[3]).premultiply();
+                    [c.red(), c.green(), c.blue(), c.alpha()] }).collect();
+                skia::IntSize::from_wh(img.width(), img.height()).and_then(|size|
+                    skia::Pixmap::from_vec(data, size)).ok_or("Invalid image".into())
+            })));
+    images
+}
+
+/// Fidelity of converting an SVG into TinyVG: the TinyVG rendered by tiny-skia compared
+/// to the SVG rendered by resvg, and the SVG features lost in the conversion.
+#[cfg(feature = "fidelity")] pub struct Fidelity { pub diff: ImageDiff, pub diff_image: skia::Pixmap, pub lost: Lost }
+
+#[cfg(feature = "fidelity")] impl std::fmt::Display for Fidelity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", self.diff)?;
+        if self.lost.is_empty() { return writeln!(f, "No unsupported features") }
+        self.lost.iter().try_for_each(|(feature, count)|
+            writeln!(f, "Unsupported {feature}: {count} times"))
+    }
+}
+
+/// Check the fidelity of converting the SVG data `svgd`, both rendered with `opts`.
+#[cfg(feature = "fidelity")] pub fn svg_fidelity(svgd: &[u8], opts: &RenderOptions)
+    -> Result<Fidelity, Box<dyn std::error::Error>> {
+    let tree = parse_svg(svgd)?;
+    let (tvg, lost) = TVGBuf::from_usvg_tree(svgd, &tree)?;
+    let img = tvg.render_with(opts)?;
+
+    let (width, height, trfm) = opts.layout(tvg.header.width, tvg.header.height);
+    let mut refi = skia::Pixmap::new(width, height).ok_or("Fail to create pixmap")?;
+    if let Some(color) = opts.background { refi.fill(color.into()) }
+    resvg::render(&tree, trfm, &mut refi.as_mut());
+
+    Ok(Fidelity { diff: compare(&img, &refi)?, diff_image: diff_image(&img, &refi)?, lost })
+}
+
+/// Luma of the pixels composed over white.
+fn luma(img: &skia::Pixmap) -> Vec<f64> {
+    img.data().chunks_exact(4).map(|px| {   let bg = 255.0 - px[3] as f64;
+        0.299 * (px[0] as f64 + bg) + 0.587 * (px[1] as f64 + bg) + 0.114 * (px[2] as f64 + bg)
+    }).collect()
+}
+
+/// Mean SSIM of the windows of 8×8 (or smaller at the edges) every 4 pixels.
+fn ssim(a: &[f64], b: &[f64], width: usize, height: usize) -> f64 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+    let (mut sum, mut count) = (0.0, 0);
+
+    for y in (0..height.saturating_sub(4).max(1)).step_by(4) {
+        for x in (0..width.saturating_sub(4).max(1)).step_by(4) {
+            let (mut sa, mut sb, mut n) = (0.0, 0.0, 0.0);
+            let (mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0);
+            for j in y..(y + 8).min(height) { for i in x..(x + 8).min(width) {
+                let (va, vb) = (a[j * width + i], b[j * width + i]);    n += 1.0;
+                sa += va;   sb += vb;   saa += va * va;     sbb += vb * vb;     sab += va * vb;
+            } }
+            if n == 0.0 { continue }
+
+            let (ma, mb) = (sa / n, sb / n);
+            let (va, vb, cov) = (saa / n - ma * ma, sbb / n - mb * mb, sab / n - ma * mb);
+            sum += (2.0 * ma * mb + C1) * (2.0 * cov + C2) /
+                   ((ma * ma + mb * mb + C1) * (va + vb + C2));  count += 1;
+        }
+    }   if count == 0 { 1.0 } else { sum / count as f64 }
+}
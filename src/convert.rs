@@ -1,21 +1,53 @@
 
-use crate::tinyvg::*;
-use std::{error::Error, io};
+use crate::{tinyvg::*, trailer::{Layer, NamedRange}};
+use std::{collections::BTreeMap, error::Error, io};
 use usvg::tiny_skia_path as skia;
 
-pub trait Convert { fn from_usvg(svgd: &[u8]) ->
-    Result<Self, Box<dyn Error>> where Self: std::marker::Sized;
+pub trait Convert {
+    fn from_usvg(svgd: &[u8]) -> Result<Self, Box<dyn Error>> where Self: std::marker::Sized {
+        let (tvg, lost) = Self::from_usvg_tree(svgd, &parse_svg(svgd)?)?;
+        lost.iter().for_each(|(feature, count)|
+            eprintln!("TinyVG can't support {feature} ({count} times)"));     Ok(tvg)
+    }
+
+    /// Convert the `tree` parsed from the SVG data `svgd`, also returning the SVG features
+    /// lost in the conversion, with how many times each was found.
+    fn from_usvg_tree(svgd: &[u8], tree: &usvg::Tree) ->
+        Result<(Self, Lost), Box<dyn Error>> where Self: std::marker::Sized;
 }
 
-impl<R: io::Read, W: io::Write> Convert for TinyVG<R, W> {
-    fn from_usvg(svgd: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let mut usvg_opts = usvg::Options::default();
-        usvg_opts.fontdb_mut().load_system_fonts();
-        let tree = usvg::Tree::from_data(svgd, &usvg_opts)?;    //&std::fs::read(&path)?,
+/// SVG features which TinyVG can't represent, dropped in conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unsupported {  ClipPath, Mask, Filter, GroupOpacity,
+    Pattern, MultiStopGradient, GradientTransform, DashArray, RasterImage,
+}
 
-        //Ok(Self::from_usvg(&tree)) }
-        //fn from_usvg(tree: &usvg::Tree) -> Self {
+/// Lost features with their number of occurrences.
+pub type Lost = BTreeMap<Unsupported, usize>;
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ClipPath => "clip-path",  Self::Mask => "mask",   Self::Filter => "filter",
+            Self::GroupOpacity => "group opacity",  Self::Pattern => "pattern painting",
+            Self::MultiStopGradient => "multi-stop gradient",
+            Self::GradientTransform => "gradient transform",
+            Self::DashArray => "dashed stroke", Self::RasterImage => "raster image",
+        })
+    }
+}
+
+fn lose(lost: &mut Lost, feature: Unsupported) { *lost.entry(feature).or_insert(0) += 1 }
+
+/// Parse SVG data with the system fonts loaded for texts.
+pub fn parse_svg(svgd: &[u8]) -> Result<usvg::Tree, usvg::Error> {
+    let mut usvg_opts = usvg::Options::default();
+    usvg_opts.fontdb_mut().load_system_fonts();
+    usvg::Tree::from_data(svgd, &usvg_opts)     //&std::fs::read(&path)?,
+}
 
+impl<R: io::Read, W: io::Write> Convert for TinyVG<R, W> {
+    fn from_usvg_tree(svgd: &[u8], tree: &usvg::Tree) -> Result<(Self, Lost), Box<dyn Error>> {
         let mut tvg = Self::new();
         tvg.header.width  = tree.size().width() .round() as _;
         tvg.header.height = tree.size().height().round() as _;
@@ -30,30 +62,68 @@ impl<R: io::Read, W: io::Write> Convert for TinyVG<R, W> {
             (1 << range_bits) { scale_bits += 1; }  tvg.header.scale = scale_bits;
         // XXX: still need a traversely check CoordinateRange by a null writer?
 
-        convert_nodes(&mut tvg, tree.root(), &usvg::Transform::identity());
+        let (mut names, mut lost) = (vec![], Lost::new());
+        convert_nodes(&mut tvg, tree.root(), &usvg::Transform::identity(),
+            &mut names, 0, &mut lost);
+        let layers = convert_layers(svgd, tree, &names);
+        if !layers.is_empty() { tvg.set_layers(&layers); }
+        if !names.is_empty() { tvg.set_named_ranges(&names); }
         println!("{:?}, {} colors, {} cmds/paths", tvg.header,
-            tvg.color_table.len(), tvg.commands.len());     Ok(tvg)
+            tvg.color_table.len(), tvg.commands.len());     Ok((tvg, lost))
     }
 }
 
-fn convert_nodes<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>,
-    parent: &usvg::Group, trfm: &usvg::Transform) {
-    for child in parent.children() { match child {
+/// Inkscape layers (named by `inkscape:label`) become layers if there are any,
+/// otherwise top-level SVG groups with an `id` do.
+fn convert_layers(svgd: &[u8], tree: &usvg::Tree, names: &[NamedRange]) -> Vec<Layer> {
+    const INKSCAPE_NS: &str = "http://www.inkscape.org/namespaces/inkscape";
+    let mut labels = vec![];
+    let opts = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    if let Some(doc) = std::str::from_utf8(svgd).ok().and_then(|text|
+        roxmltree::Document::parse_with_options(text, opts).ok()) {
+        labels = doc.descendants().filter(|node|
+            node.attribute((INKSCAPE_NS, "groupmode")) == Some("layer"))
+            .filter_map(|node| node.attribute("id").map(|id| (id.to_owned(),
+                node.attribute((INKSCAPE_NS, "label")).unwrap_or(id).to_owned()))).collect();
+    }
+
+    if labels.is_empty() {
+        labels = tree.root().children().iter().filter_map(|child| match child {
+            usvg::Node::Group(group) if !group.id().is_empty() =>
+                Some((group.id().to_owned(), group.id().to_owned())),
+            _ => None }).collect();
+    }
+
+    labels.into_iter().filter_map(|(id, name)| {
+        let ranges: Vec<_> = names.iter().filter(|named|
+            named.name == id).map(|named| named.range.clone()).collect();
+        (!ranges.is_empty()).then_some(Layer { name, ranges })
+    }).collect()
+}
+
+/// SVG elements with an `id` are recorded as named command ranges in `names`,
+/// `depth` is the group nesting level of the children, unsupported features go to `lost`.
+fn convert_nodes<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>, parent: &usvg::Group,
+    trfm: &usvg::Transform, names: &mut Vec<NamedRange>, depth: u32, lost: &mut Lost) {
+    for child in parent.children() { let start = tvg.commands.len();   match child {
         usvg::Node::Group(group) => {   // XXX: trfm is needed on rendering only
-            if !group.filters().is_empty()  || group.mask().is_some() ||
-                group.clip_path().is_some() {
-                eprintln!("filters/mask/clip-path can't be supported in TinyVG!");
-            }   convert_nodes(tvg, group, &trfm.pre_concat(group.transform()));
+            if !group.filters().is_empty() { lose(lost, Unsupported::Filter) }
+            if group.mask().is_some() { lose(lost, Unsupported::Mask) }
+            if group.clip_path().is_some() { lose(lost, Unsupported::ClipPath) }
+            if group.opacity().get() < 1.0 { lose(lost, Unsupported::GroupOpacity) }
+            convert_nodes(tvg, group, &trfm.pre_concat(group.transform()),
+                names, depth + 1, lost);
         }
 
         usvg::Node::Path(path) => if path.is_visible() {
             let (coll, mut lwidth) = (convert_path(path.data(), trfm), 0.0);
 
             let fill = path  .fill().and_then(|fill|
-                convert_paint(tvg, fill.paint(), fill.opacity(), trfm));
+                convert_paint(tvg, fill.paint(), fill.opacity(), trfm, lost));
             let line = path.stroke().and_then(|line| {
                 lwidth = line.width().get();    // XXX: need to apply transform?
-                convert_paint(tvg, line.paint(), line.opacity(), trfm) });
+                if line.dasharray().is_some() { lose(lost, Unsupported::DashArray) }
+                convert_paint(tvg, line.paint(), line.opacity(), trfm, lost) });
 
             //match path.paint_order() {} // XXX:
             let cmd = match (fill, line) {
@@ -70,14 +140,19 @@ fn convert_nodes<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>,
             match img.kind() {
                 usvg::ImageKind::GIF(_) | usvg::ImageKind::WEBP(_) |
                 usvg::ImageKind::PNG(_) | usvg::ImageKind::JPEG(_) =>
-                    eprintln!("TinyVG can't support raster images"),
-                usvg::ImageKind::SVG(svg) => convert_nodes(tvg, svg.root(), trfm),
+                    lose(lost, Unsupported::RasterImage),
+                usvg::ImageKind::SVG(svg) =>
+                    convert_nodes(tvg, svg.root(), trfm, names, depth + 1, lost),
             }
         }
 
         usvg::Node::Text(text) => { let group = text.flattened();
-            convert_nodes(tvg, group, &trfm.pre_concat(group.transform()));
+            convert_nodes(tvg, group, &trfm.pre_concat(group.transform()),
+                names, depth + 1, lost);
         }
+    }   if !child.id().is_empty() && start < tvg.commands.len() {
+            names.push(NamedRange { name: child.id().to_owned(),
+                range: start..tvg.commands.len(), depth });
     } }
 }
 
@@ -116,8 +191,13 @@ fn convert_path(path: &skia::Path, trfm: &usvg::Transform) -> Vec<Segment> {
     }   if !cmds.is_empty() { coll.push(Segment { start, cmds }); }     coll
 }
 
-fn convert_paint<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>,
-    paint: &usvg::Paint, opacity: usvg::Opacity, _trfm: &usvg::Transform) -> Option<Style> {
+fn convert_paint<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>, paint: &usvg::Paint,
+    opacity: usvg::Opacity, _trfm: &usvg::Transform, lost: &mut Lost) -> Option<Style> {
+    let mut check = |stops: &[usvg::Stop], trfm: usvg::Transform| {
+        if 2 < stops.len() { lose(lost, Unsupported::MultiStopGradient) }
+        if !trfm.is_identity() { lose(lost, Unsupported::GradientTransform) }
+    };
+
     let get_color = |stop: &usvg::Stop| {
         let color = stop.color();
         RGBA8888 { r: color.red, g: color.green, b: color.blue,
@@ -130,12 +210,12 @@ fn convert_paint<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>,
     }
 
     match paint { usvg::Paint::Pattern(_) => {  // trfm should be applied here
-            eprintln!("Not support pattern painting"); None },
+            lose(lost, Unsupported::Pattern);  None },
         usvg::Paint::Color(color) => {
             Some(Style::FlatColor(tvg.push_color(RGBA8888 { r: color.red,
                 g: color.green, b: color.blue, a: opacity.to_u8() })))
         }
-        usvg::Paint::LinearGradient(grad) => {
+        usvg::Paint::LinearGradient(grad) => {  check(grad.stops(), grad.transform());
             let (first, last) = (grad.stops().first()?, grad.stops().last()?);
             let p0 = (grad.x1(), grad.y1()).into();
             let p1 = (grad.x2(), grad.y2()).into();
@@ -143,7 +223,7 @@ fn convert_paint<R: io::Read, W: io::Write>(tvg: &mut TinyVG<R, W>,
             let c1 = tvg.push_color(get_color(last));
             Some(Style::LinearGradient { points: (p0, p1), cindex: (c0, c1) })
         }
-        usvg::Paint::RadialGradient(grad) => {
+        usvg::Paint::RadialGradient(grad) => {  check(grad.stops(), grad.transform());
             let (first, last) = (grad.stops().first()?, grad.stops().last()?);
             let p0 = (grad.fx(), grad.fy()).into(); // focus/start, center/end
             let p1 = (grad.cx(), grad.cy() + grad.r().get()).into();
@@ -0,0 +1,266 @@
+use crate::tinyvg::*;
+use std::{io, ops::Range};
+
+type Result<T> = std::result::Result<T, TVGError>;
+
+/// **Editing**: every edit is checked by `validate_command`, like `save_data` does,
+/// so an edited document can always be written out. Named ranges and layers in
+/// the trailer follow the inserted/removed commands.
+impl<R: io::Read, W: io::Write> TinyVG<R, W> {
+    pub fn push_command(&mut self, cmd: Command) -> Result<()> {
+        self.insert_command(self.commands.len(), cmd)
+    }
+
+    pub fn insert_command(&mut self, idx: usize, cmd: Command) -> Result<()> {
+        if self.commands.len() < idx { return Err(TVGError {
+            kind: ErrorKind::OutOfRange, msg: "invalid command index" }) }
+        self.validate_command(&cmd)?;
+        self.commands.insert(idx, cmd);     self.shift_ranges(idx, true);   Ok(())
+    }
+
+    pub fn remove_command(&mut self, idx: usize) -> Result<Command> {
+        if self.commands.len() <= idx { return Err(TVGError {
+            kind: ErrorKind::OutOfRange, msg: "invalid command index" }) }
+        let cmd = self.commands.remove(idx);    self.shift_ranges(idx, false);  Ok(cmd)
+    }
+
+    /// Move a command to `to` (index after removal), changing its z-order. It keeps its
+    /// layers, and leaves the named ranges it's moved out of, which never cover other
+    /// commands: moving one into the middle of a range it isn't in fails.
+    pub fn move_command(&mut self, from: usize, to: usize) -> Result<()> {
+        let len = self.commands.len();
+        if len <= from || len <= to { return Err(TVGError {
+            kind: ErrorKind::OutOfRange, msg: "invalid command index" }) }
+        self.validate_command(&self.commands[from])?;   // nothing changed if invalid
+
+        let moved = |idx: usize| if idx == from { to }
+            else if from < idx && idx <= to { idx - 1 }
+            else if to <= idx && idx < from { idx + 1 } else { idx };
+        let mut names = self.named_ranges();
+        for named in &mut names {
+            let (start, end) = named.range.clone().filter(|&idx| idx != from).map(moved)
+                .fold((usize::MAX, 0), |(start, end), idx| (start.min(idx), end.max(idx + 1)));
+            let (start, end) = if start < end { (start, end) } else { (to, to) };
+            if start < to && to < end && !named.range.contains(&from) { return Err(TVGError {
+                kind: ErrorKind::OutOfRange, msg: "move into a named range" }) }
+            named.range = if named.range.contains(&from) && start <= to + 1 && to <= end {
+                start.min(to)..end.max(to + 1) } else { start..end.max(start) };
+        }
+        let cmd = self.commands.remove(from);   self.commands.insert(to, cmd);
+        if !names.is_empty() {
+            names.retain(|named| !named.range.is_empty());  self.set_named_ranges(&names);
+        }
+
+        let mut layers = self.layers();         if !layers.is_empty() {
+            for layer in &mut layers {
+                let mut coll = layer.ranges.iter().flat_map(|range|
+                    range.clone().map(moved)).collect::<Vec<_>>();
+                coll.sort_unstable();   layer.ranges.clear();
+                for idx in coll {   match layer.ranges.last_mut() {
+                    Some(range) if range.end == idx => range.end += 1,
+                    _ => layer.ranges.push(idx..idx + 1),
+                } }
+            }   self.set_layers(&layers);
+        }   Ok(())
+    }
+
+    /// Replace the fill style of a fill or outline command.
+    pub fn set_fill(&mut self, idx: usize, style: Style) -> Result<()> {
+        self.set_style(idx, style, |cmd| cmd.styles_mut().0)
+    }
+    /// Replace the line style of a draw or outline command.
+    pub fn set_line(&mut self, idx: usize, style: Style) -> Result<()> {
+        self.set_style(idx, style, |cmd| cmd.styles_mut().1)
+    }
+
+    pub fn set_fill_color(&mut self, idx: usize, color: RGBA8888) -> Result<()> {
+        let style = self.flat_color(color);     self.set_fill(idx, style)
+    }
+    pub fn set_line_color(&mut self, idx: usize, color: RGBA8888) -> Result<()> {
+        let style = self.flat_color(color);     self.set_line(idx, style)
+    }
+
+    pub fn flat_color(&mut self, color: RGBA8888) -> Style {
+        Style::FlatColor(self.push_color(color))
+    }
+    pub fn linear_gradient(&mut self, points: (Point, Point),
+        colors: (RGBA8888, RGBA8888)) -> Style {
+        Style::LinearGradient { points, cindex:
+            (self.push_color(colors.0), self.push_color(colors.1)) }
+    }
+    pub fn radial_gradient(&mut self, points: (Point, Point),
+        colors: (RGBA8888, RGBA8888)) -> Style {
+        Style::RadialGradient { points, cindex:
+            (self.push_color(colors.0), self.push_color(colors.1)) }
+    }
+
+    fn set_style(&mut self, idx: usize, style: Style,
+        select: impl Fn(&mut Command) -> Option<&mut Style>) -> Result<()> {
+        let err = |msg| TVGError { kind: ErrorKind::OutOfRange, msg };
+        let cmd = self.commands.get_mut(idx).ok_or(err("invalid command index"))?;
+        let old = std::mem::replace(select(cmd).ok_or(err("no such style in command"))?, style);
+
+        if let Err(e) = self.validate_command(&self.commands[idx]) {
+            if let Some(style) = select(&mut self.commands[idx]) { *style = old }
+            return Err(e)
+        }   Ok(())
+    }
+
+    /// Drop unused and duplicate colors from the color table and remap all color indices,
+    /// returns the number of colors removed. Colors are ordered by first use.
+    /// Fails without changes if any command is invalid, e.g. by a color index out of range.
+    pub fn gc_colors(&mut self) -> Result<usize> {
+        self.commands.iter().try_for_each(|cmd| self.validate_command(cmd))?;
+        let (mut table, mut remap) = (vec![], vec![None; self.color_table.len()]);
+        let colors = std::mem::take(&mut self.color_table);
+
+        let mut remap_idx = |idx: &mut u32| {
+            let Some(entry) = remap.get_mut(*idx as usize) else { return };
+            *idx = *entry.get_or_insert_with(|| {  let color = colors[*idx as usize];
+                table.iter().position(|c: &RGBA8888| c.r == color.r && c.g == color.g &&
+                    c.b == color.b && c.a == color.a).unwrap_or_else(|| {
+                        table.push(color);  table.len() - 1 }) as u32
+            });
+        };
+
+        for cmd in &mut self.commands {
+            let (fill, line) = cmd.styles_mut();
+            for style in fill.into_iter().chain(line) {  match style {
+                Style::FlatColor(idx) => remap_idx(idx),
+                Style::LinearGradient { cindex, .. } |
+                Style::RadialGradient { cindex, .. } => {
+                    remap_idx(&mut cindex.0);   remap_idx(&mut cindex.1); }
+            } }
+        }
+
+        self.color_table = table;   Ok(colors.len() - self.color_table.len())
+    }
+
+    fn shift_ranges(&mut self, idx: usize, insert: bool) {
+        let shift = |range: &Range<usize>| {
+            let (start, end) = (range.start, range.end);
+            let (start, end) = if insert {
+                (start + (idx <= start) as usize, end + (idx < end) as usize) } else {
+                (start - (idx <  start) as usize, end - (idx < end) as usize) };
+            start..end.max(start)
+        };
+
+        let mut names = self.named_ranges();    if !names.is_empty() {
+            names.iter_mut().for_each(|named| named.range = shift(&named.range));
+            names.retain(|named| !named.range.is_empty());  self.set_named_ranges(&names);
+        }
+
+        let mut layers = self.layers();         if !layers.is_empty() {
+            for layer in &mut layers {
+                layer.ranges.iter_mut().for_each(|range| *range = shift(range));
+                layer.ranges.retain(|range| !range.is_empty());
+            }   self.set_layers(&layers);
+        }
+    }
+}
+
+impl Command {
+    /// The fill and line styles of the command, if any.
+    pub fn styles_mut(&mut self) -> (Option<&mut Style>, Option<&mut Style>) {
+        match self {    Command::EndOfDocument => (None, None),
+            Command::FillPolyg(FillCMD { fill, .. }) |
+            Command::FillRects(FillCMD { fill, .. }) |
+            Command::FillPath (FillCMD { fill, .. }) => (Some(fill), None),
+            Command::DrawLines(DrawCMD { line, .. }) |
+            Command::DrawLoop (DrawCMD { line, .. }, _) |
+            Command::DrawPath (DrawCMD { line, .. }) => (None, Some(line)),
+            Command::OutlinePolyg(fill, DrawCMD { line, .. }) |
+            Command::OutlineRects(fill, DrawCMD { line, .. }) |
+            Command::OutlinePath (fill, DrawCMD { line, .. }) => (Some(fill), Some(line)),
+        }
+    }
+}
+
+#[cfg(test)] mod tests { use super::*;
+    use crate::trailer::NamedRange;
+    type TestTVG = TinyVG<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>>;
+
+    fn rect(image: &mut TestTVG, x: f32, color: RGBA8888) -> Command {
+        Command::FillRects(FillCMD { fill: image.flat_color(color),
+            coll: vec![Rect { x, y: 0.0, w: 1.0, h: 1.0 }] })
+    }
+
+    #[test] fn edits_are_validated_and_colors_collected() {
+        let (red, blue) = (RGBA8888 { r: 255, g: 0, b: 0, a: 255 },
+                           RGBA8888 { r: 0, g: 0, b: 255, a: 255 });
+        let mut image = TestTVG::new();
+        (image.header.width, image.header.height) = (8, 8);
+
+        let cmd = rect(&mut image, 0.0, red);   image.push_command(cmd).unwrap();
+        let cmd = rect(&mut image, 2.0, blue);  image.push_command(cmd).unwrap();
+        image.set_named_ranges(&[NamedRange { name: "blue".into(), range: 1..2, depth: 0 }]);
+
+        let cmd = rect(&mut image, 1e6, red);   assert!(image.push_command(cmd).is_err());
+        assert!(image.push_command(Command::FillPolyg(FillCMD {
+            fill: Style::FlatColor(0), coll: vec![] })).is_err());
+        assert!(image.set_fill(0, Style::FlatColor(9)).is_err());
+        assert!(image.set_line(0, Style::FlatColor(0)).is_err());
+
+        let cmd = rect(&mut image, 4.0, red);   image.insert_command(0, cmd).unwrap();
+        assert_eq!(image.lookup_name("blue"), Some(2..3));
+        image.move_command(0, 2).unwrap();
+        assert_eq!(image.lookup_name("blue"), Some(1..2));
+
+        let points = (Point { x: 0.0, y: 0.0 }, Point { x: 8.0, y: 0.0 });
+        let style = image.linear_gradient(points, (red, RGBA8888 { a: 0, ..red }));
+        image.set_fill(1, style).unwrap();
+        image.set_fill_color(2, blue).unwrap();
+        image.remove_command(0).unwrap();
+
+        assert_eq!(image.gc_colors().unwrap(), 0);   assert_eq!(image.color_table.len(), 3);
+        image.set_fill_color(0, blue).unwrap();
+        assert_eq!(image.gc_colors().unwrap(), 2);   assert_eq!(image.color_table.len(), 1);
+        assert!(matches!(image.commands[1], Command::FillRects(FillCMD {
+            fill: Style::FlatColor(0), .. })));
+
+        let mut output = io::Cursor::new(vec![]);
+        image.save_data(&mut output).unwrap();  output.set_position(0);
+        assert_eq!(TestTVG::load_data(&mut output).unwrap().commands.len(), 2);
+    }
+
+    #[test] fn move_keeps_ranges_and_layers() {
+        use crate::trailer::Layer;
+        let red = RGBA8888 { r: 255, g: 0, b: 0, a: 255 };
+        let mut image = TestTVG::new();
+        (image.header.width, image.header.height) = (8, 8);
+        for x in 0..4 {
+            let cmd = rect(&mut image, x as _, red);    image.push_command(cmd).unwrap();
+        }
+        image.set_named_ranges(&[NamedRange { name: "pair".into(), range: 1..3, depth: 0 }]);
+        image.set_layers(&[Layer { name: "hidden".into(), ranges: vec![1..2, 2..3] }]);
+        let xs = |image: &TestTVG| image.commands.iter().map(|cmd| match cmd {
+            Command::FillRects(FillCMD { coll, .. }) => coll[0].x as i32, _ => -1 })
+            .collect::<Vec<_>>();
+
+        image.move_command(1, 2).unwrap();  // reorder inside the layer
+        assert_eq!(xs(&image), [0, 2, 1, 3]);
+        assert_eq!(image.lookup_name("pair"), Some(1..3));
+        assert_eq!(image.layer_mask(Some(&[])), [true, false, false, true]);
+
+        image.move_command(1, 3).unwrap();  // out of the layer, still in it
+        assert_eq!(xs(&image), [0, 1, 3, 2]);
+        assert_eq!(image.layers()[0].ranges, [1..2, 3..4]);
+        assert_eq!(image.lookup_name("pair"), Some(1..2));  // never covering others
+        assert_eq!(image.layer_mask(Some(&[])), [true, false, true, false]);
+
+        image.set_named_ranges(&[NamedRange { name: "pair".into(), range: 1..3, depth: 0 }]);
+        assert!(image.move_command(0, 1).is_err());     // into the middle of it
+        image.move_command(3, 0).unwrap();  // next to it, not into it
+        assert_eq!(image.lookup_name("pair"), Some(2..4));
+        image.move_command(2, 0).unwrap();  // out of it
+        image.move_command(3, 0).unwrap();  // its last command, followed
+        assert_eq!((xs(&image), image.lookup_name("pair")), (vec![3, 1, 2, 0], Some(0..1)));
+
+        assert!(image.move_command(0, 4).is_err() && image.move_command(4, 0).is_err());
+        image.commands[2] = Command::FillRects(FillCMD { fill: Style::FlatColor(9),
+            coll: vec![Rect { x: 0.0, y: 0.0, w: 1.0, h: 1.0 }] });
+        assert!(image.move_command(2, 0).is_err());
+        assert!(image.gc_colors().is_err());
+        assert_eq!((image.commands.len(), image.color_table.len()), (4, 1));    // nothing lost
+    }
+}
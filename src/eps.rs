@@ -0,0 +1,133 @@
+
+//! EPS (Encapsulated PostScript) export of TinyVG: paths with curves and native arcs,
+//! gradients into PostScript level 3 shadings, clipped to the image size.
+
+use crate::{tinyvg::*, canvas::{Canvas, svg_arc}, render::gradient_stops,
+    pdf::{function, channel}, pathops::PathOps};
+use std::{convert::Infallible, io};
+
+pub trait EpsExport {
+    /// Write an EPS with the bounding box of what's drawn in the image. PostScript has
+    /// no transparency, so translucent colors are composed over white as on paper.
+    fn save_eps(&self, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl<R: io::Read, W: io::Write> EpsExport for TinyVG<R, W> {
+    fn save_eps(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let (width, height) = (self.header.width, self.header.height);
+        let mut canvas = EpsCanvas { content: String::new(), path: PathOps::new(),
+            shadings: vec![] };
+        for cmd in &self.commands { let _ = canvas.draw_command(self, cmd); }
+
+        let (w, h) = (width as f32, height as f32);     // flipped up and clipped
+        let (x0, y0, x1, y1) = self.commands.iter().filter_map(Command::bounds)
+            .map(|r| (r.x.max(0.0), (h - r.y - r.h).max(0.0),
+                      (r.x + r.w).min(w), (h - r.y).min(h)))
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+            .filter(|bbox| bbox.0 < bbox.2 && bbox.1 < bbox.3).unwrap_or_default();
+
+        writeln!(writer, "%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: {} {} {} {}\n\
+            %%HiResBoundingBox: {x0} {y0} {x1} {y1}\n%%Creator: {} {}\n%%LanguageLevel: 3\n\
+            %%EndComments", x0.floor(), y0.floor(), x1.ceil(), y1.ceil(),
+            env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
+
+        writeln!(writer, "%%BeginProlog\n/m /moveto load def /l /lineto load def \
+            /c /curveto load def /h /closepath load def\n\
+            /re {{ 4 2 roll moveto 1 index 0 rlineto 0 exch rlineto neg 0 rlineto closepath }} \
+            bind def\n\
+            % elliptical arc: angle0 angle1 sweep rx ry rotation cx cy\n\
+            /ea {{ matrix currentmatrix 9 1 roll translate rotate scale\n  \
+            {{ 0 0 1 5 3 roll arc }} {{ 0 0 1 5 3 roll arcn }} ifelse setmatrix }} bind def")?;
+        for (idx, shading) in canvas.shadings.iter().enumerate() {
+            writeln!(writer, "/Sh{idx} {shading} def")?;
+        }
+
+        writeln!(writer, "%%EndProlog\ngsave\n0 {height} translate 1 -1 scale \
+            0 0 {width} {height} rectclip\n1 setlinecap 1 setlinejoin\n\
+            {}grestore\nshowpage\n%%EOF", canvas.content)
+    }
+}
+
+/// Paint of a color, or a shading defined in the prolog.
+pub(crate) enum EpsPaint { Color(String), Shading(usize) }
+
+/// Content in image coordinates (flipped from PostScript), the current path is built
+/// in `path`, and the shading dictionaries in `shadings`.
+struct EpsCanvas { content: String, path: PathOps, shadings: Vec<String> }
+
+impl EpsCanvas {
+    fn shading(&mut self, shading: String) -> EpsPaint {
+        EpsPaint::Shading(self.shadings.iter().position(|sh| *sh == shading)
+            .unwrap_or_else(|| { self.shadings.push(shading);  self.shadings.len() - 1 }))
+    }
+}
+
+impl Canvas for EpsCanvas {
+    type Paint = EpsPaint;  type Error = Infallible;
+
+    /// Colors composed over white, as there is no transparency in PostScript.
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        let rgb = |c: RGBA8888| {   let over = |v: u8|
+                channel(((v as u32 * c.a as u32 + 255 * (255 - c.a as u32) + 127) / 255) as _);
+            format!("{} {} {}", over(c.r), over(c.g), over(c.b))
+        };
+        let shading = |kind: u8, coords: String, cindex: &(u32, u32)| {
+            let stops = gradient_stops(img.lookup_color(cindex.0),
+                img.lookup_color(cindex.1), false);     // in linear light by default
+            format!("<< /ShadingType {kind} /ColorSpace /DeviceRGB /Coords [{coords}] \
+                /Function {} /Extend [true true] >>", function(&stops, rgb))
+        };
+
+        Ok(match style {
+            Style::FlatColor(idx) => EpsPaint::Color(rgb(img.lookup_color(*idx))),
+            Style::LinearGradient { points: (p0, p1), cindex } => self.shading(shading(2,
+                format!("{} {} {} {}", p0.x, p0.y, p1.x, p1.y), cindex)),
+            Style::RadialGradient { points: (p0, p1), cindex } => self.shading(shading(3,
+                format!("{0} {1} 0 {0} {1} {2}", p0.x, p0.y,
+                    (p1.x - p0.x).hypot(p1.y - p0.y)), cindex)),
+        })
+    }
+
+    fn move_to (&mut self, pt: Point) { self.path.move_to(pt) }
+    fn line_to (&mut self, pt: Point) { self.path.line_to(pt) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) { self.path.quad_to(ctrl, end) }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) { self.path.cubic_to(ctrl, end) }
+    /// Circular arcs by `arc`/`arcn`, elliptical ones by `ea` in the prolog.
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        let Some(arc) = kurbo::Arc::from_svg_arc(&svg_arc(start,
+            radii, rotation, large, sweep, end)) else { self.path.line_to(end);  return Ok(()) };
+
+        let (a0, a1) = (arc.start_angle.to_degrees() as f32,
+            (arc.start_angle + arc.sweep_angle).to_degrees() as f32);
+        let (center, positive) = ((arc.center.x as f32, arc.center.y as f32),
+            0.0 < arc.sweep_angle);
+        let (rx, ry) = (arc.radii.x as f32, arc.radii.y as f32);
+        let ops = if rx == ry { format!("{} {} {rx} {a0} {a1} {}\n",
+                center.0, center.1, if positive { "arc" } else { "arcn" })
+        } else { format!("{a0} {a1} {positive} {rx} {ry} {} {} {} ea\n",
+                arc.x_rotation.to_degrees() as f32, center.0, center.1)
+        };  self.path.segment(&ops, end);   Ok(())
+    }
+    fn close(&mut self) { self.path.close() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> { self.path.rect(rect);  Ok(()) }
+
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.content.push_str(&match paint {
+            EpsPaint::Color(rgb) => format!("gsave {rgb} setrgbcolor newpath\n{}fill grestore\n",
+                self.path.ops),
+            EpsPaint::Shading(idx) => format!("gsave newpath\n{}clip Sh{idx} shfill grestore\n",
+                self.path.ops),
+        }); Ok(())
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.content.push_str(&match paint {
+            EpsPaint::Color(rgb) => format!("gsave {width} setlinewidth {rgb} setrgbcolor \
+                newpath\n{}stroke grestore\n", self.path.ops),
+            EpsPaint::Shading(idx) => format!("gsave {width} setlinewidth newpath\n\
+                {}strokepath clip Sh{idx} shfill grestore\n", self.path.ops),
+        }); Ok(())
+    }
+    fn clear (&mut self) { self.path.clear() }
+}
@@ -34,7 +34,7 @@ macro_rules! evg_debug {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)] pub struct EvgError(i32);
 impl EvgError {
     fn out_of_memory() -> Self { Self(GF_Err::GF_OUT_OF_MEM as i32) }
-    fn bad_parameter() -> Self { Self(GF_Err::GF_BAD_PARAM  as i32) }
+    pub(crate) fn bad_parameter() -> Self { Self(GF_Err::GF_BAD_PARAM  as i32) }
     pub fn code(self) -> i32 { self.0 }
 }
 impl core::fmt::Display for EvgError {
@@ -342,6 +342,16 @@ impl Pixmap {
         let len = width * format.bpp().ok_or(EvgError::bad_parameter())? * height;
         Ok(Self { data: vec![0; len as _], width, height, format })
     }
+    pub fn from_vec(data: Vec<u8>, width: u32, height: u32,
+        format: GF_PixelFormat) -> Result<Self, EvgError> {
+        let len = width * format.bpp().ok_or(EvgError::bad_parameter())? * height;
+        if data.len() != len as usize { return Err(EvgError::bad_parameter()) }
+        Ok(Self { data, width, height, format })
+    }
+
+    pub fn data(&self) -> &[u8] { &self.data }
+    pub fn width (&self) -> u32 { self.width }
+    pub fn height(&self) -> u32 { self.height }
 
     pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), std::io::Error> {
         let mut encoder = png::Encoder::new(std::io::BufWriter::new(
@@ -0,0 +1,250 @@
+
+//! A pure-Rust port of the anti-aliasing rasterizer in FreeType `ftgrays.c`: path edges
+//! accumulate signed area and cover into the cells of a pixel grid, then a sweep of each
+//! row turns the cells into spans of coverage, handed over to a callback to paint.
+
+use crate::tinyvg::Point;
+
+const PIXEL_BITS: i32 = 8;  // 24.8 fixed point coordinates
+const ONE_PIXEL:  i32 = 1 << PIXEL_BITS;
+const MAX_COORD:  i32 = 1 << 24;    // keeps the products of deltas in i64
+/// Max. distance in pixels of flattened Bézier curves from the exact ones.
+const TOLERANCE:  f32 = 0.2;
+
+/// A run of `len` pixels from `x` on a row, all with the same `coverage` (255 for full).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span { pub x: i32, pub len: u32, pub coverage: u8 }
+
+/// Which pixels are inside of overlapping or self-intersecting contours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule { #[default] NonZero, EvenOdd }
+
+/// Twice the signed `area` of a cell covered to the right of the edges within,
+/// and the `cover`, the edges' signed vertical extent, both in subpixels.
+#[derive(Debug, Clone, Copy, Default)] struct Cell { x: i32, cover: i32, area: i32 }
+
+/// Coverage accumulating rasterizer clipped to `width`x`height` pixels, fed with
+/// contours in pixel coordinates, which are closed implicitly.
+pub struct Rasterizer {
+    width: i32, height: i32,    rows: Vec<Vec<Cell>>,
+    /// The cell being accumulated, on row `ey`.
+    cell: Cell, ey: i32,
+    /// The current and contour start points, in fixed point.
+    pos: (i32, i32), start: (i32, i32),
+}
+
+impl Rasterizer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width: width as _, height: height as _, rows: vec![vec![]; height as _],
+            cell: Cell::default(), ey: -1, pos: (0, 0), start: (0, 0) }
+    }
+
+    pub fn width (&self) -> u32 { self.width  as _ }
+    pub fn height(&self) -> u32 { self.height as _ }
+
+    /// Start a new contour at `pt`, closing the current one.
+    pub fn move_to(&mut self, pt: Point) {
+        self.close();   let pos = fixed(pt);
+        self.set_cell(pos.0 >> PIXEL_BITS, pos.1 >> PIXEL_BITS);
+        (self.pos, self.start) = (pos, pos);
+    }
+    pub fn line_to(&mut self, pt: Point) { self.render_line(fixed(pt)) }
+
+    /// Flatten into lines uniformly, as many as Wang's formula requires for `TOLERANCE`.
+    pub fn quad_to(&mut self, ctrl: Point, end: Point) {
+        let p0 = self.current();
+        let dd = (p0.x - 2.0 * ctrl.x + end.x).hypot(p0.y - 2.0 * ctrl.y + end.y);
+        let n = segments(dd / 4.0);
+
+        for i in 1..=n {    let t = i as f32 / n as f32;    let mt = 1.0 - t;
+            let (a, b, c) = (mt * mt, 2.0 * mt * t, t * t);
+            self.line_to(Point { x: a * p0.x + b * ctrl.x + c * end.x,
+                                 y: a * p0.y + b * ctrl.y + c * end.y });
+        }
+    }
+    pub fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        let (p0, (p1, p2)) = (self.current(), ctrl);
+        let dd = (p0.x - 2.0 * p1.x + p2.x).hypot(p0.y - 2.0 * p1.y + p2.y)
+            .max((p1.x - 2.0 * p2.x + end.x).hypot(p1.y - 2.0 * p2.y + end.y));
+        let n = segments(dd * 3.0 / 4.0);
+
+        for i in 1..=n {    let t = i as f32 / n as f32;    let mt = 1.0 - t;
+            let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+            self.line_to(Point { x: a * p0.x + b * p1.x + c * p2.x + d * end.x,
+                                 y: a * p0.y + b * p1.y + c * p2.y + d * end.y });
+        }
+    }
+    pub fn close(&mut self) { if self.pos != self.start { self.render_line(self.start) } }
+
+    /// Add a kurbo path, e.g. one stroked or transformed by kurbo.
+    pub fn add_path(&mut self, path: impl IntoIterator<Item = kurbo::PathEl>) {
+        let pt = |pt: kurbo::Point| Point { x: pt.x as _, y: pt.y as _ };
+        for el in path { match el {
+            kurbo::PathEl::MoveTo(p0) => self.move_to(pt(p0)),
+            kurbo::PathEl::LineTo(p1) => self.line_to(pt(p1)),
+            kurbo::PathEl::QuadTo(p1, p2) => self.quad_to(pt(p1), pt(p2)),
+            kurbo::PathEl::CurveTo(p1, p2, p3) => self.cubic_to((pt(p1), pt(p2)), pt(p3)),
+            kurbo::PathEl::ClosePath  => self.close(),
+        } }
+    }
+
+    /// Sweep the accumulated cells row by row from the top, calling `spans` with each row
+    /// index and its spans from left to right, then reset for the next path.
+    pub fn render(&mut self, rule: FillRule, mut spans: impl FnMut(i32, &[Span])) {
+        self.close();   self.record();  self.ey = -1;
+        let mut line = Vec::new();
+
+        for (y, cells) in self.rows.iter_mut().enumerate() {
+            if cells.is_empty() { continue }
+            cells.sort_unstable_by_key(|cell| cell.x);
+            let (mut cover, mut x) = (0i64, cells[0].x);
+
+            for group in cells.chunk_by(|a, b| a.x == b.x) {
+                let cell = group.iter().skip(1).fold(group[0], |mut acc, cell| {
+                    acc.cover += cell.cover;    acc.area += cell.area;  acc });
+                if x < cell.x && cover != 0 {
+                    hline(&mut line, rule, self.width, x, cell.x - x,
+                        cover * (ONE_PIXEL as i64 * 2));
+                }
+
+                cover += cell.cover as i64;
+                let area = cover * (ONE_PIXEL as i64 * 2) - cell.area as i64;
+                if area != 0 { hline(&mut line, rule, self.width, cell.x, 1, area) }
+                x = cell.x + 1;
+            }
+            if cover != 0 && x < self.width {
+                hline(&mut line, rule, self.width, x, self.width - x,
+                    cover * (ONE_PIXEL as i64 * 2));
+            }
+
+            if !line.is_empty() { spans(y as _, &line);    line.clear(); }  cells.clear();
+        }
+    }
+
+    fn current(&self) -> Point {
+        Point { x: self.pos.0 as f32 / ONE_PIXEL as f32, y: self.pos.1 as f32 / ONE_PIXEL as f32 }
+    }
+
+    /// Move to the cell at (`ex`, `ey`), cells beyond the left/right edges merge into one,
+    /// which just carries the cover.
+    fn set_cell(&mut self, ex: i32, ey: i32) {
+        let ex = ex.clamp(-1, self.width);
+        if ex != self.cell.x || ey != self.ey {
+            self.record();  self.cell = Cell { x: ex, cover: 0, area: 0 };  self.ey = ey;
+        }
+    }
+
+    fn record(&mut self) {
+        if (self.cell.area | self.cell.cover) != 0 && (0..self.height).contains(&self.ey) {
+            self.rows[self.ey as usize].push(self.cell)
+        }   self.cell.area = 0;     self.cell.cover = 0;
+    }
+
+    /// Render a line within the row `ey`, from `x1` to `x2` with the fractional `y1`/`y2`.
+    fn render_scanline(&mut self, ey: i32, x1: i32, y1: i32, x2: i32, y2: i32) {
+        let (ex1, ex2) = (x1 >> PIXEL_BITS, x2 >> PIXEL_BITS);
+        let (fx1, fx2) = (x1 & (ONE_PIXEL - 1), x2 & (ONE_PIXEL - 1));
+
+        if y1 == y2 { return self.set_cell(ex2, ey) }   // horizontal, just moves
+        if ex1 == ex2 {     let delta = y2 - y1;    // within a cell
+            self.cell.area += (fx1 + fx2) * delta;  self.cell.cover += delta;   return
+        }
+
+        let (dx, dy) = ((x2 - x1) as i64, (y2 - y1) as i64);
+        let (p, first, incr, dx) = if dx < 0 { (fx1 as i64 * dy, 0, -1, -dx)
+        } else { ((ONE_PIXEL - fx1) as i64 * dy, ONE_PIXEL, 1, dx) };
+
+        let (delta, mut rem) = (p.div_euclid(dx) as i32, p.rem_euclid(dx));
+        self.cell.area += (fx1 + first) * delta;    self.cell.cover += delta;
+        let (mut ex1, mut y1) = (ex1 + incr, y1 + delta);   self.set_cell(ex1, ey);
+
+        if ex1 != ex2 {     let p = ONE_PIXEL as i64 * dy;
+            let (lift, step) = (p.div_euclid(dx) as i32, p.rem_euclid(dx));    rem -= dx;
+            while ex1 != ex2 {
+                let mut delta = lift;   rem += step;
+                if 0 <= rem { rem -= dx;    delta += 1; }
+                self.cell.area += ONE_PIXEL * delta;    self.cell.cover += delta;
+                y1 += delta;    ex1 += incr;    self.set_cell(ex1, ey);
+            }
+        }
+
+        let delta = y2 - y1;
+        self.cell.area += (fx2 + ONE_PIXEL - first) * delta;    self.cell.cover += delta;
+    }
+
+    /// Render a line from the current point to `to`, row by row.
+    fn render_line(&mut self, to: (i32, i32)) {
+        let ((x, y), (ex2, ey2)) = (self.pos, (to.0 >> PIXEL_BITS, to.1 >> PIXEL_BITS));
+        let mut ey1 = y >> PIXEL_BITS;
+        let (fy1, fy2) = (y & (ONE_PIXEL - 1), to.1 & (ONE_PIXEL - 1));
+        self.pos = to;
+
+        if (self.height <= ey1 && self.height <= ey2) || (ey1 < 0 && ey2 < 0) {
+            return self.set_cell(ex2, ey2)  // vertically clipped
+        }
+        if ey1 == ey2 { return self.render_scanline(ey1, x, fy1, to.0, fy2) }
+
+        let (dx, dy) = ((to.0 - x) as i64, (to.1 - y) as i64);
+        let (first, incr) = if dy < 0 { (0, -1) } else { (ONE_PIXEL, 1) };
+        if dx == 0 {    // vertical, in a column of cells
+            let (ex, two_fx) = (x >> PIXEL_BITS, (x & (ONE_PIXEL - 1)) << 1);
+            let delta = first - fy1;
+            self.cell.area += two_fx * delta;   self.cell.cover += delta;
+            ey1 += incr;    self.set_cell(ex, ey1);
+
+            let delta = first + first - ONE_PIXEL;
+            while ey1 != ey2 {
+                self.cell.area += two_fx * delta;   self.cell.cover += delta;
+                ey1 += incr;    self.set_cell(ex, ey1);
+            }
+
+            let delta = fy2 - ONE_PIXEL + first;
+            self.cell.area += two_fx * delta;   self.cell.cover += delta;   return
+        }
+
+        let (p, dy) = if dy < 0 { (fy1 as i64 * dx, -dy)
+        } else { ((ONE_PIXEL - fy1) as i64 * dx, dy) };
+        let (delta, mut rem) = (p.div_euclid(dy) as i32, p.rem_euclid(dy));
+        let mut x1 = x + delta;     self.render_scanline(ey1, x, fy1, x1, first);
+        ey1 += incr;    self.set_cell(x1 >> PIXEL_BITS, ey1);
+
+        if ey1 != ey2 {     let p = ONE_PIXEL as i64 * dx;
+            let (lift, step) = (p.div_euclid(dy) as i32, p.rem_euclid(dy));    rem -= dy;
+            while ey1 != ey2 {
+                let mut delta = lift;   rem += step;
+                if 0 <= rem { rem -= dy;    delta += 1; }
+                let x2 = x1 + delta;
+                self.render_scanline(ey1, x1, ONE_PIXEL - first, x2, first);    x1 = x2;
+                ey1 += incr;    self.set_cell(x1 >> PIXEL_BITS, ey1);
+            }
+        }   self.render_scanline(ey1, x1, ONE_PIXEL - first, to.0, fy2);
+    }
+}
+
+fn fixed(pt: Point) -> (i32, i32) {
+    let v = |v: f32| ((v * ONE_PIXEL as f32).round() as i32).clamp(-MAX_COORD, MAX_COORD);
+    (v(pt.x), v(pt.y))
+}
+
+/// Number of lines to flatten a curve with the max. second difference `dd` (scaled).
+fn segments(dd: f32) -> u32 { ((dd / TOLERANCE).sqrt().ceil() as u32).clamp(1, 256) }
+
+/// Append a span of `len` pixels from `x` with the doubled `area` in subpixels,
+/// clipped to `width`, merged into the last span if adjacent with the same coverage.
+fn hline(line: &mut Vec<Span>, rule: FillRule, width: i32, x: i32, len: i32, area: i64) {
+    let coverage = (area >> (PIXEL_BITS * 2 + 1 - 8)).abs();
+    let coverage = match rule {
+        FillRule::NonZero => coverage.min(255),
+        FillRule::EvenOdd => match coverage & 511 {
+            256 => 255, coverage if 256 < coverage => 512 - coverage, coverage => coverage,
+        },
+    } as u8;
+
+    let (x0, x1) = (x.max(0), (x + len).min(width));
+    if coverage == 0 || x1 <= x0 { return }
+    if let Some(last) = line.last_mut() {
+        if last.x + last.len as i32 == x0 && last.coverage == coverage {
+            last.len += (x1 - x0) as u32;   return
+        }
+    }   line.push(Span { x: x0, len: (x1 - x0) as _, coverage });
+}
@@ -1,7 +1,19 @@
 
 pub mod tinyvg;
 pub mod render;
+pub mod canvas;
+pub mod grays;
+pub mod render_grays;
+pub mod compare;
 pub mod convert;
+pub mod tvgt;
+pub mod svg;
+pub mod pdf;
+pub mod eps;
+mod pathops;
+pub mod codegen;
+pub mod trailer;
+pub mod edit;
 
 #[cfg(feature = "ovg")] pub mod openvg;
 #[cfg(feature = "b2d")] pub mod blend2d;
@@ -15,6 +27,7 @@ pub mod convert;
 }
 
 /*  TODO: https://github.com/sammycage/plutovg
+    ftgrays.c is ported to Rust as `grays`, see also:
     https://github.com/kiba/SDL_svg/blob/master/ftgrays.c
     https://github.com/Samsung/rlottie/tree/master/src/vector/freetype
     https://gitlab.freedesktop.org/freetype/freetype/-/blob/master/src/smooth/ftgrays.c
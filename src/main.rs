@@ -3,36 +3,134 @@
 
 #[cfg_attr(coverage_nightly, coverage(off))] //#[cfg(not(tarpaulin_include))]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    use std::{env, fs::{self, File}, io::{BufReader, BufWriter}};
-    use intvg::{tinyvg::TVGImage, render::Render, convert::Convert};
+    use std::{env, fs::{self, File}, io::{BufReader, BufWriter, Write}};
+    use intvg::{tinyvg::TVGImage, render::Render, convert::Convert, tvgt::TextFormat};
 
     eprintln!(r"{} v{}-g{}, {}, {} 🦀", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"),
         env!("BUILD_GIT_HASH"), env!("BUILD_TIMESTAMP"), env!("CARGO_PKG_AUTHORS"));
         //build_time::build_time_local!("%H:%M:%S%:z %Y-%m-%d"),    //option_env!("ENV_VAR_NAME");
 
     let (cnt, mut args) = (env::args().count(), env::args());
-    if   cnt < 2 { println!("Usage: {} <path-to-svg/tvg> [<path-to-tvg/png>] [-R <b2d|evg>]\n",
+    if   cnt < 2 { println!("Usage: {} <path-to-svg/tvg/tvgt> \
+            [<path-to-tvg/tvgt/svg/pdf/eps/js/rs/c/png/apng/html>] \
+            [-R|-P <b2d|evg|grays>|-D]\n       \
+            {0} <path-to-svg/tvg/tvgt> compare [<reference.png>]\n       \
+            {0} <path-to-svg> fidelity\n",
             args.next().unwrap());  return Ok(())   //env!("CARGO_BIN_NAME")
     }   // all unwrap are safe
 
     let mut path = args.nth(1).unwrap();
     let tvg = if path.ends_with(".tvg") {
         TVGImage::load_data(&mut BufReader::new(File::open(&path)?))?
-    } else if path.ends_with(".svg") { TVGImage::from_usvg(&fs::read(&path)?)?
-    } else { return Err("Only support .svg & .tvg files".into()) };
+    } else if path.ends_with(".svg") {
+        let mut tvg = TVGImage::from_usvg(&fs::read(&path)?)?;
+        let mut meta = tvg.metadata();  if let Some(name) =
+            std::path::Path::new(&path).file_name() { meta.set_source(&name.to_string_lossy()) }
+        tvg.set_metadata(&meta);    tvg
+    } else if path.ends_with(".tvgt") { TVGImage::load_text(&fs::read_to_string(&path)?)?
+    } else { return Err("Only support .svg, .tvg & .tvgt files".into()) };
 
+    let input = path.clone();
     if 2 < cnt { path = args.next().unwrap(); } else {
         path.replace_range(path.rfind('.').unwrap_or(path.len()).., ".png");
     }
 
-    if  path.ends_with(".tvg") {
+    if  path == "compare" {  // how the backends differ from tiny-skia or a reference PNG
+        use intvg::{compare::*, render::RenderOptions};
+        let images = render_backends(&tvg, &RenderOptions::default());
+        let (base, refi) = match args.next() {
+            Some(file) => { let refi = tiny_skia::Pixmap::load_png(&file)?;  (file, refi) }
+            None => ("tiny-skia".to_owned(), images[0].1.clone()?),
+        };
+
+        println!("Compared to {base}:");
+        for (backend, img) in &images {
+            let img = match img { Ok(img) => img,
+                Err(err) => { println!("{backend:>10}: {err}");  continue }
+            };
+            let diff = match compare(img, &refi) { Ok(diff) => diff,
+                Err(err) => { println!("{backend:>10}: {err}");  continue }
+            };  println!("{backend:>10}: {diff}");
+            if 0 < diff.pixels {
+                let stem = input.rfind('.').map_or(input.as_str(), |pos| &input[..pos]);
+                diff_image(img, &refi)?.save_png(format!("{stem}_{}_diff.png",
+                    backend.replace('/', "-")))?;
+            }
+        }
+    } else if path == "fidelity" {   // how faithful the TinyVG is to the SVG source
+        #[cfg(not(feature = "fidelity"))] return Err("built without feature `fidelity`".into());
+        #[cfg(feature = "fidelity")] {
+            if !input.ends_with(".svg") { return Err("fidelity needs a .svg file".into()) }
+            let report = intvg::compare::svg_fidelity(&fs::read(&input)?,
+                &intvg::render::RenderOptions::default())?;     print!("{report}");
+            report.diff_image.save_png(format!("{}_fidelity.png",
+                input.trim_end_matches(".svg")))?;
+        }
+    } else if path.ends_with(".tvg") {
         tvg.save_data(&mut BufWriter::new(fs::OpenOptions::new()
             .write(true).create_new(true).open(path)?))?;
+    } else if path.ends_with(".tvgt") {
+        tvg.save_text(&mut BufWriter::new(fs::OpenOptions::new()
+            .write(true).create_new(true).open(path)?))?;
+    } else if path.ends_with(".svg") {
+        use intvg::svg::SvgExport;
+        tvg.save_svg(&mut BufWriter::new(fs::OpenOptions::new()
+            .write(true).create_new(true).open(path)?))?;
+    } else if path.ends_with(".pdf") {
+        use intvg::pdf::PdfExport;
+        tvg.save_pdf(&mut BufWriter::new(fs::OpenOptions::new()
+            .write(true).create_new(true).open(path)?))?;
+    } else if path.ends_with(".eps") {
+        use intvg::eps::EpsExport;
+        tvg.save_eps(&mut BufWriter::new(fs::OpenOptions::new()
+            .write(true).create_new(true).open(path)?))?;
+    } else if let Some(lang) = std::path::Path::new(&path).extension()
+        .and_then(|ext| intvg::codegen::Lang::from_ext(&ext.to_string_lossy())) {
+        use intvg::codegen::{CodeGen, identifier};  // named after the file
+        let name = std::path::Path::new(&path).file_stem().map_or("tvg".into(), |stem|
+            identifier(&stem.to_string_lossy()));
+        tvg.save_code(lang, &name, &mut BufWriter::new(fs::OpenOptions::new()
+            .write(true).create_new(true).open(&path)?))?;
+    } else if path.ends_with(".html") {     // image map of named SVG elements
+        let name = std::path::Path::new(&path).file_stem()
+            .map_or("tvg".into(), |stem| stem.to_string_lossy());
+        fs::OpenOptions::new().write(true).create_new(true).open(&path)?
+            .write_all(tvg.html_image_map(&name, 1.0).as_bytes())?;
+    } else if path.ends_with(".apng") {    // built up one command per frame
+        use intvg::render::{PreparedScene, RenderOptions, save_apng};
+        if std::path::Path::new(&path).exists() { return Err("output file exists".into()) }
+        let opts = RenderOptions::default();
+        save_apng(PreparedScene::new(&tvg, &opts)?.build_up(&opts,
+            intvg::tinyvg::RGBA8888 { r: 255, g: 0, b: 255, a: 255 })?, path, 100)?;
     } else if path.ends_with(".png") {
         if std::path::Path::new(&path).exists() { return Err("output file exists".into()) }
 
         let tnow = std::time::Instant::now();
-        if args.next().is_some_and(|arg| arg == "-R") {
+        let flag = args.next();
+        if flag.as_ref().is_some_and(|arg| arg == "-D") {     // debug overlay
+            let opts = intvg::render::RenderOptions { debug: true, ..Default::default() };
+            tvg.render_with(&opts)?.save_png(path)?;
+        } else if flag.as_ref().is_some_and(|arg| arg == "-P") {     // profiling
+            let (opts, eng) = (intvg::render::RenderOptions::default(), args.next());
+            let heatmap = format!("{}_overdraw.png", path.trim_end_matches(".png"));
+            let profile = match eng.as_deref() {
+                #[cfg(feature = "b2d")] Some("b2d") => {
+                    let (img, profile) = intvg::render_b2d::Render::profile(&tvg, &opts)?;
+                    img.write_to_file(path)?;   profile
+                }
+                #[cfg(feature = "evg")] Some("evg") => {
+                    let (img, profile) = intvg::render_evg::Render::profile(&tvg, &opts)?;
+                    img.save_png(path)?;    profile
+                }
+                Some("grays") => {
+                    let (img, profile) = intvg::render_grays::Render::profile(&tvg, &opts)?;
+                    img.save_png(path)?;    profile
+                }
+                _ => { let (img, profile) = tvg.profile(&opts)?; img.save_png(path)?; profile }
+            };
+            print!("{profile}");
+            if let Some(img) = profile.heatmap() { img.save_png(heatmap)? }
+        } else if flag.is_some_and(|arg| arg == "-R") {
             let eng = args.next().expect("need to specify a renderer!");
 
             #[cfg(feature = "b2d")] if eng == "b2d" {
@@ -40,6 +138,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             #[cfg(feature = "evg")] if eng == "evg" {
                 return Ok(intvg::render_evg::Render::render(&tvg, 1.0)?.save_png(path)?)
+            }
+            if eng == "grays" {
+                return Ok(intvg::render_grays::Render::render(&tvg, 1.0)?.save_png(path)?)
             }   eprintln!("Not support `{eng}' rendering!");
         } else { tvg.render(1.0)?.save_png(path)?; }
         eprintln!("Rendering performance: {:.2} fps", 1.0 / tnow.elapsed().as_secs_f32());
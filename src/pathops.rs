@@ -0,0 +1,37 @@
+//! Path construction for PDF and EPS in the same operators (`m`, `l`, `c`, `h`, `re`,
+//! defined in the prolog of EPS), quadratic Béziers elevated to cubic.
+//! A line width of 0 is the thinnest line of the device in both, as in TinyVG.
+
+use crate::tinyvg::{Point, Rect};
+
+/// Operators of the current path, and its start and last point for closing and curves.
+pub(crate) struct PathOps { pub ops: String, start: Point, last: Point }
+
+impl PathOps {
+    pub fn new() -> Self { Self { ops: String::new(),
+        start: Point { x: 0.0, y: 0.0 }, last: Point { x: 0.0, y: 0.0 } } }
+
+    pub fn move_to (&mut self, pt: Point) {
+        self.ops.push_str(&format!("{} {} m\n", pt.x, pt.y));   (self.start, self.last) = (pt, pt);
+    }
+    pub fn line_to (&mut self, pt: Point) {
+        self.ops.push_str(&format!("{} {} l\n", pt.x, pt.y));   self.last = pt;
+    }
+    pub fn quad_to (&mut self, ctrl: Point, end: Point) {
+        let (p0, f) = (self.last, 2.0 / 3.0);
+        self.cubic_to((Point { x: p0.x + (ctrl.x - p0.x) * f, y: p0.y + (ctrl.y - p0.y) * f },
+            Point { x: end.x + (ctrl.x - end.x) * f, y: end.y + (ctrl.y - end.y) * f }), end)
+    }
+    pub fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.ops.push_str(&format!("{} {} {} {} {} {} c\n",
+            ctrl.0.x, ctrl.0.y, ctrl.1.x, ctrl.1.y, end.x, end.y));      self.last = end;
+    }
+    /// Append operators `ops` of other segments ending at `end`, e.g. native arcs.
+    pub fn segment(&mut self, ops: &str, end: Point) { self.ops.push_str(ops);  self.last = end; }
+    pub fn close(&mut self) { self.ops.push_str("h\n");    self.last = self.start; }
+    pub fn rect (&mut self, rect: &Rect) {
+        self.ops.push_str(&format!("{} {} {} {} re\n", rect.x, rect.y, rect.w, rect.h));
+        (self.start, self.last) = (Point { x: rect.x, y: rect.y }, Point { x: rect.x, y: rect.y });
+    }
+    pub fn clear(&mut self) { self.ops.clear() }
+}
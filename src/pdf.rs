@@ -0,0 +1,172 @@
+
+//! PDF export of TinyVG in vector: a single page of the size in the header, with commands
+//! into path operators, gradients into shading patterns and transparency by ExtGState.
+
+use crate::{tinyvg::*, canvas::{Canvas, svg_arc}, render::gradient_stops, pathops::PathOps};
+use std::{convert::Infallible, io};
+
+pub trait PdfExport {
+    /// Write a self-contained PDF 1.4 document, the objects uncompressed.
+    fn save_pdf(&self, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl<R: io::Read, W: io::Write> PdfExport for TinyVG<R, W> {
+    fn save_pdf(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let (width, height) = (self.header.width, self.header.height);
+        let mut canvas = PdfCanvas { content: format!("1 0 0 -1 0 {height} cm\n1 J 1 j\n"),
+            path: PathOps::new(), objects: vec![], gstates: vec![], patterns: vec![],
+            size: (width, height) };
+        for cmd in &self.commands { let _ = canvas.draw_command(self, cmd); }
+
+        let resource = |prefix: &str, refs: &[usize]| refs.iter().enumerate()
+            .map(|(idx, obj)| format!(" /{prefix}{idx} {obj} 0 R")).collect::<String>();
+        let mut objects = vec!["<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_owned(),
+            format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {width} {height}] /Resources \
+                << /ExtGState <<{}>> /Pattern <<{}>> >> /Contents 4 0 R >>",
+                resource("GS", &canvas.gstates), resource("P", &canvas.patterns)),
+            stream("", &canvas.content)];
+        objects.append(&mut canvas.objects);
+
+        let mut data = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (idx, obj) in objects.iter().enumerate() {     offsets.push(data.len());
+            data.extend(format!("{} 0 obj\n{obj}\nendobj\n", idx + 1).as_bytes());
+        }
+
+        let xref = data.len();
+        data.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets { data.extend(format!("{offset:010} 00000 n \n").as_bytes()); }
+        data.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1).as_bytes());     writer.write_all(&data)
+    }
+}
+
+/// Paint operators for filling and stroking, with the graphics state of its transparency.
+pub(crate) struct PdfPaint { fill: String, stroke: String, gstate: Option<usize> }
+
+/// Content stream in image coordinates (flipped from PDF), the current path is built
+/// in `path`, and the indirect objects (numbered from 5) of the resources in `objects`.
+struct PdfCanvas { content: String, path: PathOps, objects: Vec<String>,
+    gstates: Vec<usize>, patterns: Vec<usize>, size: (u32, u32) }
+
+impl PdfCanvas {
+    fn object(&mut self, obj: String) -> usize { self.objects.push(obj);  self.objects.len() + 4 }
+
+    /// Index of the named ExtGState `dict` in resources, deduplicated.
+    fn gstate(&mut self, dict: String) -> usize {
+        if let Some(idx) = self.gstates.iter().position(|obj|
+            self.objects[obj - 5] == dict) { return idx }
+        let obj = self.object(dict);    self.gstates.push(obj);     self.gstates.len() - 1
+    }
+
+    /// Shading of the gradient along `coords` by the `stops`, axial or radial,
+    /// into a pattern, and a soft mask of the alpha of stops if not the same.
+    fn shading(&mut self, kind: u8, coords: String,
+        stops: &[(f32, RGBA8888)]) -> PdfPaint {
+        let height = self.size.1;
+        let shading = |space: &str, func: String| format!("<< /ShadingType {kind} \
+            /ColorSpace /{space} /Coords [{coords}] /Function {func} /Extend [true true] >>");
+        let rgb = shading("DeviceRGB", function(stops, |c|
+            format!("{} {} {}", channel(c.r), channel(c.g), channel(c.b))));
+        let pattern = format!("<< /PatternType 2 /Shading {rgb} \
+            /Matrix [1 0 0 -1 0 {height}] >>");    // into image coordinates
+        let idx = self.patterns.iter().position(|obj| self.objects[obj - 5] == pattern)
+            .unwrap_or_else(|| {    let obj = self.object(pattern);
+                self.patterns.push(obj);    self.patterns.len() - 1 });
+
+        let alpha = stops[0].1.a;
+        let gstate = if stops.iter().all(|(_, c)| c.a == alpha) {
+            (alpha < 255).then(|| self.gstate(format!("<< /Type /ExtGState \
+                /ca {0} /CA {0} >>", channel(alpha))))
+        } else {
+            let gray = shading("DeviceGray", function(stops, |c| channel(c.a)));
+            let (width, height) = self.size;
+            let form = self.object(stream(&format!("/Type /XObject /Subtype /Form \
+                /BBox [0 0 {width} {height}] /Group << /S /Transparency /CS /DeviceGray >> \
+                /Resources << /Shading << /Sh0 {gray} >> >> "), "/Sh0 sh\n"));
+            Some(self.gstate(format!("<< /Type /ExtGState \
+                /SMask << /Type /Mask /S /Luminosity /G {form} 0 R >> >>")))
+        };
+
+        PdfPaint { fill: format!("/Pattern cs /P{idx} scn"),
+            stroke: format!("/Pattern CS /P{idx} SCN"), gstate }
+    }
+
+    fn paint_path(&mut self, paint: &PdfPaint, ops: &str, paint_op: &str) {
+        let gstate = paint.gstate.map_or(String::new(), |idx| format!("/GS{idx} gs "));
+        self.content.push_str(&format!("q {gstate}{ops}\n{}{paint_op} Q\n", self.path.ops));
+    }
+}
+
+impl Canvas for PdfCanvas {
+    type Paint = PdfPaint;  type Error = Infallible;
+
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        let stops = |cindex: &(u32, u32)|    // XXX: interpolated in linear light by default
+            gradient_stops(img.lookup_color(cindex.0), img.lookup_color(cindex.1), false);
+
+        Ok(match style {
+            Style::FlatColor(idx) => {  let c = img.lookup_color(*idx);
+                let rgb = format!("{} {} {}", channel(c.r), channel(c.g), channel(c.b));
+                PdfPaint { fill: format!("{rgb} rg"), stroke: format!("{rgb} RG"),
+                    gstate: (c.a < 255).then(|| self.gstate(format!("<< /Type /ExtGState \
+                        /ca {0} /CA {0} >>", channel(c.a)))) }
+            }
+            Style::LinearGradient { points: (p0, p1), cindex } => self.shading(2,
+                format!("{} {} {} {}", p0.x, p0.y, p1.x, p1.y), &stops(cindex)),
+            Style::RadialGradient { points: (p0, p1), cindex } => self.shading(3,
+                format!("{0} {1} 0 {0} {1} {2}", p0.x, p0.y,
+                    (p1.x - p0.x).hypot(p1.y - p0.y)), &stops(cindex)),
+        })
+    }
+
+    fn move_to (&mut self, pt: Point) { self.path.move_to(pt) }
+    fn line_to (&mut self, pt: Point) { self.path.line_to(pt) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) { self.path.quad_to(ctrl, end) }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) { self.path.cubic_to(ctrl, end) }
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        let pt = |p: kurbo::Point| Point { x: p.x as _, y: p.y as _ };
+        match kurbo::Arc::from_svg_arc(&svg_arc(start, radii, rotation, large, sweep, end)) {
+            None => self.path.line_to(end),
+            Some(arc) => arc.to_cubic_beziers(0.1, |p1, p2, p|
+                self.path.cubic_to((pt(p1), pt(p2)), pt(p))),
+        }   Ok(())
+    }
+    fn close(&mut self) { self.path.close() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> { self.path.rect(rect);  Ok(()) }
+
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.paint_path(paint, &paint.fill, "f");   Ok(())
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.paint_path(paint, &format!("{width} w {}", paint.stroke), "S");    Ok(())
+    }
+    fn clear (&mut self) { self.path.clear() }
+}
+
+/// Exponential interpolation function of the colors between the (sorted) `stops`,
+/// stitched if more than 2, with the color components by `comps`.
+pub(crate) fn function(stops: &[(f32, RGBA8888)],
+    comps: impl Fn(RGBA8888) -> String) -> String {
+    let exp = |c0, c1| format!("<< /FunctionType 2 /Domain [0 1] \
+        /C0 [{}] /C1 [{}] /N 1 >>", comps(c0), comps(c1));
+    if let [(_, c0), (_, c1)] = stops { return exp(*c0, *c1) }
+
+    let funcs = stops.windows(2).map(|pair| exp(pair[0].1, pair[1].1)).collect::<Vec<_>>();
+    let bounds = stops[1..stops.len() - 1].iter().map(|(pos, _)|
+        pos.to_string()).collect::<Vec<_>>();
+    format!("<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+        funcs.join(" "), bounds.join(" "), vec!["0 1"; funcs.len()].join(" "))
+}
+
+fn stream(dict: &str, data: &str) -> String {
+    format!("<< {dict}/Length {} >>\nstream\n{data}endstream", data.len())
+}
+
+#[inline] pub(crate) fn channel(c: u8) -> String {
+    let v = format!("{:.3}", c as f32 / 255.0);
+    v.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
@@ -1,168 +1,736 @@
 
-use crate::tinyvg::*;
+use crate::{tinyvg::*, canvas::{Canvas, svg_arc}};
 use tiny_skia as skia;
-use std::{io, result::Result};
+use std::{io, ops::Range, result::Result, time::{Duration, Instant}};
 
-pub trait Render { fn render(&self, scale: f32) -> Result<skia::Pixmap, &str>; }
+pub trait Render {
+    fn render(&self, scale: f32) -> Result<skia::Pixmap, &str> {
+        self.render_with(&RenderOptions { scale, ..Default::default() })
+    }
+    /// Render with only the `visible` named layers switched on, or all if `None`.
+    fn render_layers(&self, scale: f32, visible: Option<&[&str]>) -> Result<skia::Pixmap, &str> {
+        self.render_with(&RenderOptions { scale, layers: visible.map(|names|
+            names.iter().map(|name| name.to_string()).collect()), ..Default::default() })
+    }
+    fn render_with(&self, opts: &RenderOptions) -> Result<skia::Pixmap, &str>;
+
+    /// Draw straight into an existing pixmap with `trfm` from image to pixel coordinates,
+    /// clipped by an optional `mask` and blended with a global `opacity`.
+    fn render_into(&self, pixmap: &mut skia::PixmapMut, trfm: skia::Transform,
+        mask: Option<&skia::Mask>, opacity: f32) -> Result<(), &str>;
+
+    /// Render just the commands for which `filter` with their index returns true,
+    /// e.g. to step through a conversion; see also the `commands` option.
+    fn render_filtered(&self, opts: &RenderOptions,
+        filter: &dyn Fn(usize, &Command) -> bool) -> Result<skia::Pixmap, &str>;
+
+    /// Render single-threaded and profile each command, see `Profile`.
+    fn profile(&self, opts: &RenderOptions) -> Result<(skia::Pixmap, Profile), &str>;
+}
+
+/// How the image is fitted into the target size, like SVG `preserveAspectRatio`
+/// with `meet` (`Contain`), `slice` (`Cover`) or `none` (`Stretch`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fit { #[default] Contain, Cover, Stretch }
+
+/// Alignment of each axis in the target size, like `xMin`/`xMid`/`xMax`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align { Min, #[default] Mid, Max }
+
+/// Downsampling filter for supersampling: average of each N×N block (`Box`),
+/// or the sharper windowed sinc (`Lanczos`, 3 lobes) which may ring a bit on edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter { #[default] Box, Lanczos }
+
+/// Options honored the same way by every rendering backend.
+#[derive(Debug, Clone)] pub struct RenderOptions {
+    /// Target size in pixels, or the image size times `scale` (rounded up) if `None`.
+    pub size: Option<(u32, u32)>,   pub scale: f32,
+    pub fit: Fit,   pub align: (Align, Align),
+    /// Extra affine transform in pixel coordinates, applied after fitting.
+    pub transform: skia::Transform,
+    pub background: Option<RGBA8888>,
+    /// Only these named layers are visible, or all if `None`.
+    pub layers: Option<Vec<String>>,
+    /// Interpolate gradients in sRGB (legacy), instead of linear light per the spec.
+    pub srgb_gradient: bool,
+
+    /// Anti-aliasing, switch off for crisp pixel-art icons (no supersampling then).
+    /// Blend2D and Canvas2D always anti-alias: off just turns off supersampling by Blend2D,
+    /// and Canvas2D ignores it.
+    pub anti_alias: bool,
+    /// Render at `supersample`×`supersample` the size and downsample by `filter`.
+    pub supersample: u32,   pub filter: Filter,
+    /// Accumulate coverage in linear light instead of sRGB, by supersampling
+    /// (4×4 if `supersample` is less than 2).
+    pub gamma_correct: bool,
+    /// Only render this pixel rectangle of the output, e.g. a tile of a deep zoom.
+    pub region: Option<skia::IntRect>,
+    /// Render in parallel bands on this many threads, 0 for as many as CPU cores.
+    pub threads: usize,
+    /// Only render the commands in this index range, e.g. `0..n + 1` to stop after command n.
+    pub commands: Option<Range<usize>>,
+    /// Draw a debug overlay on top (by the tiny-skia renderer only): path outlines,
+    /// Bézier handles, segment starts with direction arrows, arc centers and bounding
+    /// boxes labeled with their command index.
+    pub debug: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self { Self { size: None, scale: 1.0, fit: Fit::Contain,
+        align: (Align::Mid, Align::Mid), transform: skia::Transform::identity(),
+        background: None, layers: None, srgb_gradient: false,
+        anti_alias: true, supersample: 1, filter: Filter::Box, gamma_correct: false,
+        region: None, threads: 1, commands: None, debug: false,
+    } }
+}
+
+impl RenderOptions {
+    /// Pixel size of the output and the transform from image to pixel coordinates,
+    /// for an image of `width`x`height`, just of the `region` if any.
+    pub fn layout(&self, width: u32, height: u32) -> (u32, u32, skia::Transform) {
+        let (pw, ph, trfm) = self.full_layout(width, height);
+        let Some(rect) = self.region else { return (pw, ph, trfm) };
+        (rect.width(), rect.height(), trfm.post_translate(-rect.x() as _, -rect.y() as _))
+    }
+
+    /// Split the whole output for an image of `width`x`height` into `tile` sized regions
+    /// (smaller at the right and bottom edges), row by row, to render them one by one.
+    pub fn tiles(&self, width: u32, height: u32,
+        tile: (u32, u32)) -> impl Iterator<Item = RenderOptions> + '_ {
+        let (pw, ph, _) = self.full_layout(width, height);
+        let (tw, th) = (tile.0.max(1), tile.1.max(1));
+
+        (0..ph.div_ceil(th)).flat_map(move |row| (0..pw.div_ceil(tw)).map(move |col| {
+            let (x, y) = (col * tw, row * th);
+            RenderOptions { region: skia::IntRect::from_xywh(x as _, y as _,
+                tw.min(pw - x), th.min(ph - y)), ..self.clone() }
+        }))
+    }
+
+    fn full_layout(&self, width: u32, height: u32) -> (u32, u32, skia::Transform) {
+        let (iw, ih) = (width as f32, height as f32);
+        let Some((pw, ph)) = self.size else {
+            return ((iw * self.scale).ceil() as _, (ih * self.scale).ceil() as _,
+                skia::Transform::from_scale(self.scale, self.scale).post_concat(self.transform))
+        };
+
+        let (sx, sy) = (pw as f32 / iw, ph as f32 / ih);
+        let (sx, sy) = match self.fit {   Fit::Stretch => (sx, sy),
+            Fit::Contain => (sx.min(sy), sx.min(sy)), Fit::Cover => (sx.max(sy), sx.max(sy)),
+        };
+        let offset = |align, room: f32| match align {
+            Align::Min => 0.0, Align::Mid => room / 2.0, Align::Max => room };
+
+        (pw, ph, skia::Transform::from_row(sx, 0.0, 0.0, sy,
+            offset(self.align.0, pw as f32 - iw * sx),
+            offset(self.align.1, ph as f32 - ih * sy)).post_concat(self.transform))
+    }
+
+    /// Per-command visibility of `img` by the `layers` and `commands` options.
+    pub fn layer_mask<R: io::Read, W: io::Write>(&self, img: &TinyVG<R, W>) -> Vec<bool> {
+        let names = self.layers.as_ref().map(|names|
+            names.iter().map(String::as_str).collect::<Vec<_>>());
+        let mut mask = img.layer_mask(names.as_deref());
+        if let Some(range) = &self.commands {
+            mask.iter_mut().enumerate().for_each(|(i, show)| *show &= range.contains(&i))
+        }   mask
+    }
+
+    /// Per-command visibility of `img` by the `layers` and `commands` options, culling
+    /// the commands whose bounds under `trfm` are outside of the `width`x`height` pixels.
+    pub fn visible<R: io::Read, W: io::Write>(&self, img: &TinyVG<R, W>,
+        trfm: &skia::Transform, width: u32, height: u32) -> Vec<bool> {
+        let margin = min_line_width(trfm) * self.samples() as f32 / 2.0;
+        let (right, bottom) = (width as f32 + 1.0, height as f32 + 1.0); // with AA fringe
+
+        self.layer_mask(img).into_iter().zip(&img.commands).map(|(show, cmd)| show &&
+            cmd.bounds().and_then(|r| skia::Rect::from_xywh(r.x - margin, r.y - margin,
+                r.w + margin * 2.0, r.h + margin * 2.0)).and_then(|r| r.transform(*trfm))
+                .is_none_or(|r| r.left() < right && -1.0 < r.right() &&
+                                r.top() < bottom && -1.0 < r.bottom())).collect()
+    }
+
+    /// Effective supersampling factor per axis.
+    pub fn samples(&self) -> u32 {
+        if !self.anti_alias { 1 } else if self.gamma_correct && self.supersample < 2 { 4 }
+        else { self.supersample.max(1) }
+    }
+}
 
 impl<R: io::Read, W: io::Write> Render for TinyVG<R, W> {
-    fn render(&self, scale: f32) -> Result<skia::Pixmap, &str> {
-        let mut pixmap = skia::Pixmap::new(
-            (self.header.width  as f32 * scale).ceil() as _,
-            (self.header.height as f32 * scale).ceil() as _).ok_or("Fail to create pixmap")?;
+    fn render_with(&self, opts: &RenderOptions) -> Result<skia::Pixmap, &str> {
+        PreparedScene::new(self, opts)?.render_with(opts)
+    }
+
+    fn render_into(&self, pixmap: &mut skia::PixmapMut, trfm: skia::Transform,
+        mask: Option<&skia::Mask>, opacity: f32) -> Result<(), &str> {
+        PreparedScene::new(self, &RenderOptions::default())?
+            .render_into(pixmap, trfm, mask, opacity)
+    }
+
+    fn render_filtered(&self, opts: &RenderOptions,
+        filter: &dyn Fn(usize, &Command) -> bool) -> Result<skia::Pixmap, &str> {
+        PreparedScene::with_filter(self, opts, filter)?.render_with(opts)
+    }
+
+    fn profile(&self, opts: &RenderOptions) -> Result<(skia::Pixmap, Profile), &str> {
+        let (scene, tnow) = (PreparedScene::new(self, opts)?, Instant::now());
+        let mut times = vec![];     let pixmap = scene.render_timed(opts, &mut times)?;
+        Ok((pixmap, Profile::from_scene(&scene, opts, "tiny-skia", &times, tnow.elapsed())))
+    }
+}
+
+/// Costs of a command in a profiling render.
+#[derive(Debug, Clone, Default)] pub struct CommandCost {
+    /// Index of the command, and the time the backend took drawing it.
+    pub index: usize,   pub time: Duration,
+    /// Output pixels covered by it, and its path complexity (path verbs, arcs as cubics).
+    pub pixels: u64,    pub verbs: usize,
+}
+
+/// Result of a profiling render by a backend: costs of each drawn command,
+/// and the overdraw heatmap, how many times each output pixel was painted.
+/// The pixels are counted by the path coverage at output resolution, the same for
+/// every backend, just the times differ.
+#[derive(Debug, Clone, Default)] pub struct Profile {
+    pub backend: &'static str,  pub total: Duration,    pub commands: Vec<CommandCost>,
+    pub width: u32, pub height: u32,    pub overdraw: Vec<u16>,
+}
+
+impl Profile {
+    /// Profile `img` as prepared with `opts`, with the drawing `times` of commands
+    /// by index (from `render_timed` of a backend) and the `total` rendering time.
+    pub fn new<R: io::Read, W: io::Write>(img: &TinyVG<R, W>, opts: &RenderOptions,
+        backend: &'static str, times: &[(usize, Duration)],
+        total: Duration) -> Result<Self, &'static str> {
+        Ok(Self::from_scene(&PreparedScene::new(img, opts)?, opts, backend, times, total))
+    }
 
-        // XXX: rendering up-scale and then scale down for anti-aliasing?
-        let trfm = skia::Transform::from_scale(scale, scale);
+    pub fn from_scene(scene: &PreparedScene, opts: &RenderOptions, backend: &'static str,
+        times: &[(usize, Duration)], total: Duration) -> Self {
+        let (width, height, trfm) = opts.layout(scene.width, scene.height);
+        let mut profile = Self { backend, total, commands: vec![], width, height,
+            overdraw: vec![0; (width * height) as usize] };
+        let Some(mut mask) = skia::Mask::new(width, height) else { return profile };
+
+        let (hairline, res_scale) = (min_line_width(&trfm),
+            skia::PathStroker::compute_resolution_scale(&trfm));
         let mut stroke = skia::Stroke { line_join: skia::LineJoin::Round,
             line_cap: skia::LineCap::Round, ..Default::default() };
-        let err_msg = "Fail to build path";
+        let mut spent = vec![Duration::ZERO; scene.items.last().map_or(0, |item| item.index + 1)];
+        for (index, time) in times {
+            if let Some(spent) = spent.get_mut(*index) { *spent += *time } }
+
+        for group in scene.items.chunk_by(|a, b| a.index == b.index) {
+            mask.clear();
+            for item in group {
+                let path = match item.lwidth {  None => Some(item.path.clone()),
+                    Some(lwidth) => {   stroke.width = lwidth.max(hairline);
+                        item.path.stroke(&stroke, res_scale) }
+                };
+                if let Some(path) = path { mask.fill_path(&path,
+                    skia::FillRule::Winding, opts.anti_alias, trfm) }
+            }
 
-        let fillrule = skia::FillRule::Winding;
-        for cmd in &self.commands {
-            let mut pb = skia::PathBuilder::new();
-            match cmd {     Command::EndOfDocument => (),
+            let mut pixels = 0;
+            for (count, _) in profile.overdraw.iter_mut().zip(mask.data())
+                .filter(|(_, coverage)| 0 < **coverage) {
+                *count = count.saturating_add(1);   pixels += 1;
+            }
 
-                Command::FillPolyg(FillCMD { fill, coll }) => {
-                    let mut iter = coll.iter();
-                    if let Some(pt) = iter.next() { pb.move_to(pt.x, pt.y) }
-                    iter.for_each(|pt| pb.line_to(pt.x, pt.y));  pb.close();
+            let index = group[0].index;
+            profile.commands.push(CommandCost { index, time: spent[index], pixels,
+                verbs: group.iter().map(|item| item.path.len()).sum() });
+        }   profile
+    }
 
-                    pixmap.fill_path(&pb.finish().ok_or(err_msg)?,
-                        &style_to_paint(self, fill, trfm)?, fillrule, trfm, None);
-                }
-                Command::FillRects(FillCMD { fill, coll }) => {
-                    for rect in coll { pb.push_rect(skia::Rect::from_xywh(
-                        rect.x, rect.y, rect.w, rect.h).ok_or("Invalid rectangle")?); }
-                    pixmap.fill_path(&pb.finish().ok_or(err_msg)?,
-                        &style_to_paint(self, fill, trfm)?, fillrule, trfm, None);
-                }
-                Command::FillPath (FillCMD { fill, coll }) => {
-                    for seg in coll { let _ = segment_to_path(seg, &mut pb); }
-                    pixmap.fill_path(&pb.finish().ok_or(err_msg)?,
-                        &style_to_paint(self, fill, trfm)?, fillrule, trfm, None);
-                }
-                Command::DrawLines(DrawCMD { line, lwidth, coll }) => {
-                    coll.iter().for_each(|line| {
-                        pb.move_to(line.start.x, line.start.y);
-                        pb.line_to(line.  end.x, line.  end.y);
-                    }); stroke.width = *lwidth;
-
-                    pixmap.stroke_path(&pb.finish().ok_or(err_msg)?,
-                        &style_to_paint(self, line, trfm)?, &stroke, trfm, None);
-                }
-                Command::DrawLoop (DrawCMD { line, lwidth, coll },
-                    strip) => {     let mut iter = coll.iter();
-                    if let Some(pt) = iter.next() { pb.move_to(pt.x, pt.y) }
-                    iter.for_each(|pt| pb.line_to(pt.x, pt.y));
-
-                    if !*strip { pb.close(); }  stroke.width = *lwidth;
-                    pixmap.stroke_path(&pb.finish().ok_or(err_msg)?,
-                        &style_to_paint(self, line, trfm)?, &stroke, trfm, None);
-                }
-                Command::DrawPath (DrawCMD {
-                    line, lwidth, coll }) => {
-                    let paint = style_to_paint(self, line, trfm)?;
-                    stroke.width = *lwidth;
+    /// The commands sorted by time spent, the slowest first.
+    pub fn slowest(&self) -> Vec<&CommandCost> {
+        let mut costs = self.commands.iter().collect::<Vec<_>>();
+        costs.sort_by_key(|cost| std::cmp::Reverse(cost.time));    costs
+    }
 
-                    for seg in coll {
-                        stroke_segment_path(seg, &mut pixmap, &paint, &mut stroke, trfm)?; }
-                }
-                Command::OutlinePolyg(fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let mut iter = coll.iter();
-                    if let Some(pt) = iter.next() { pb.move_to(pt.x, pt.y) }
-                    iter.for_each(|pt| pb.line_to(pt.x, pt.y));     pb.close();
-                    let path = pb.finish().ok_or(err_msg)?;     stroke.width = *lwidth;
-
-                    pixmap.  fill_path(&path,
-                        &style_to_paint(self, fill, trfm)?, fillrule, trfm, None);
-                    pixmap.stroke_path(&path,
-                        &style_to_paint(self, line, trfm)?,  &stroke, trfm, None);
-                }
-                Command::OutlineRects(fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let paint = style_to_paint(self, fill, trfm)?;
-                    let pline = style_to_paint(self, line, trfm)?;
-                    stroke.width = *lwidth;
-
-                    for rect in coll { pb.push_rect(skia::Rect::from_xywh(
-                        rect.x, rect.y, rect.w, rect.h).ok_or("Invalid rectangle")?); }
-                    let path = pb.finish().ok_or(err_msg)?;
-
-                    pixmap.  fill_path(&path, &paint, fillrule, trfm, None);
-                    pixmap.stroke_path(&path, &pline,  &stroke, trfm, None);
-                }
-                Command::OutlinePath (fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let paint = style_to_paint(self, fill, trfm)?;
-                    let pline = style_to_paint(self, line, trfm)?;
-
-                    stroke.width = *lwidth;     let mut res = false;
-                    for seg in coll { res = segment_to_path(seg, &mut pb); }
-                    let path = pb.finish().ok_or(err_msg)?;
-                    pixmap.fill_path(&path, &paint, fillrule, trfm, None);
-
-                    if res { for seg in coll {
-                        stroke_segment_path(seg, &mut pixmap, &pline, &mut stroke, trfm)?;
-                    } } else { pixmap.stroke_path(&path, &pline, &stroke, trfm, None); }
-                }
-            }
-        }   Ok(pixmap)
-    }   // rasterize
+    /// Overdraw heatmap image, black for untouched pixels, then from blue, red, yellow
+    /// to white for the most painted ones.
+    pub fn heatmap(&self) -> Option<skia::Pixmap> {
+        const RAMP: [(u8, u8, u8); 4] =
+            [(0, 0, 255), (255, 0, 0), (255, 255, 0), (255, 255, 255)];
+        let max = self.overdraw.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let mut pixmap = skia::Pixmap::new(self.width, self.height)?;
+
+        for (pixel, &count) in pixmap.pixels_mut().iter_mut().zip(&self.overdraw) {
+            let (r, g, b) = if count == 0 { (0, 0, 0) } else {
+                let pos = count as f32 / max * (RAMP.len() - 1) as f32;
+                let i = (pos as usize).min(RAMP.len() - 2);
+                let (c0, c1, f) = (RAMP[i], RAMP[i + 1], pos - i as f32);
+                let mix = |a: u8, b: u8| lerp(a as _, b as _, f).round() as u8;
+                (mix(c0.0, c1.0), mix(c0.1, c1.1), mix(c0.2, c1.2))
+            };  *pixel = skia::ColorU8::from_rgba(r, g, b, 255).premultiply();
+        }   Some(pixmap)
+    }
+}
+
+impl std::fmt::Display for Profile {
+    /// A summary and the 20 slowest commands.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let painted = self.overdraw.iter().filter(|count| 0 < **count).count().max(1);
+        let drawn = self.commands.iter().map(|cost| cost.time).sum::<Duration>();
+        writeln!(f, "{}: {:.3} ms total, {:.3} ms drawing {} commands, \
+            overdraw {:.2} on average, {} at most", self.backend,
+            self.total.as_secs_f64() * 1e3, drawn.as_secs_f64() * 1e3, self.commands.len(),
+            self.overdraw.iter().map(|count| *count as u64).sum::<u64>() as f64 / painted as f64,
+            self.overdraw.iter().max().unwrap_or(&0))?;
+
+        writeln!(f, "{:>8} {:>10} {:>10} {:>8}", "command", "time(µs)", "pixels", "verbs")?;
+        for cost in self.slowest().iter().take(20) {
+            writeln!(f, "{:>8} {:>10.1} {:>10} {:>8}", cost.index,
+                cost.time.as_secs_f64() * 1e6, cost.pixels, cost.verbs)?;
+        }   Ok(())
+    }
 }
 
-fn stroke_segment_path(seg: &Segment, pixmap: &mut skia::Pixmap, paint: &skia::Paint,
-    stroke: &mut skia::Stroke, trfm: skia::Transform) -> Result<(), &'static str> {
-    let mut pb = skia::PathBuilder::new();
-    pb.move_to(seg.start.x, seg.start.y);
+/// A TinyVG image converted once into tiny-skia paths and paints, to render it repeatedly
+/// at any transform cheaply. The `layers`, `commands`, `srgb_gradient`, `anti_alias`
+/// and `debug` options are applied on preparing, the others on each rendering.
+pub struct PreparedScene { width: u32, height: u32, items: Vec<Item>, marks: Vec<Marks> }
+
+/// A path of the command at `index` to fill, or to stroke with its line width
+/// (at least a device pixel wide).
+struct Item { index: usize, path: skia::Path, paint: skia::Paint<'static>, lwidth: Option<f32> }
+
+impl Item {
+    /// Bounds in image coordinates, including half the line width.
+    fn bounds(&self, hairline: f32) -> Option<skia::Rect> {
+        let margin = self.lwidth.map_or(0.0, |lwidth| lwidth.max(hairline) / 2.0);
+        self.path.bounds().outset(margin, margin)
+    }
+}
+
+/// Builds the items of a scene as a `Canvas`.
+struct SceneBuilder<'a> { opts: &'a RenderOptions, pb: skia::PathBuilder,
+    index: usize, items: Vec<Item> }
+
+impl SceneBuilder<'_> {
+    fn push(&mut self, paint: &skia::Paint<'static>,
+        lwidth: Option<f32>) -> Result<(), &'static str> {
+        let path = self.pb.clone().finish().ok_or("Fail to build path")?;
+        self.items.push(Item { index: self.index, path, paint: paint.clone(), lwidth }); Ok(())
+    }
+}
+
+impl Canvas for SceneBuilder<'_> {
+    type Paint = skia::Paint<'static>;  type Error = &'static str;
+
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        style_to_paint(img, style, self.opts)
+    }
+
+    fn move_to (&mut self, pt: Point) { self.pb.move_to(pt.x, pt.y) }
+    fn line_to (&mut self, pt: Point) { self.pb.line_to(pt.x, pt.y) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) {
+        self.pb.quad_to(ctrl.x, ctrl.y, end.x, end.y)
+    }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.pb.cubic_to(ctrl.0.x, ctrl.0.y, ctrl.1.x, ctrl.1.y, end.x, end.y)
+    }
+
+    //  https://github.com/RazrFalcon/resvg/blob/master/crates/usvg/src/parser/shapes.rs#L287
+    //  https://medium.com/@Ovilia/if-you-wish-to-draw-an-arc-in-a-web-page-then-you-should-probably-use-canvas-or-svg-5a81194bbdf7
+    //  https://stackoverflow.com/questions/6729056/mapping-svg-arcto-to-html-canvas-arcto
+    //  https://spencermortensen.com/articles/bezier-circle/
+
+    //  SVG arc to Canvas arc: https://github.com/nical/lyon/blob/main/crates/geom/src/arc.rs
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        if let Some(arc) = kurbo::Arc::from_svg_arc(&svg_arc(start,
+            radii, rotation, large, sweep, end)) {
+            arc.to_cubic_beziers(0.1, |p1, p2, end|
+                self.pb.cubic_to(p1.x as _, p1.y as _,
+                                 p2.x as _, p2.y as _, end.x as _, end.y as _))
+        } else { self.pb.line_to(end.x, end.y) }    Ok(())
+    }
 
-    for cmd in &seg.cmds {
-        if let Some(width) = cmd.lwidth {
-            if 1 < pb.len() {   let err_msg = "no start";
-                let start = pb.last_point().ok_or(err_msg)?;
-                pixmap.stroke_path(&pb.finish().ok_or(err_msg)?, paint, stroke, trfm, None);
-                pb = skia::PathBuilder::new();  pb.move_to(start.x, start.y);
-            }   stroke.width = width;
-        }   process_segcmd(&mut pb, &cmd.instr);
+    fn close(&mut self) { self.pb.close() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        self.pb.push_rect(skia::Rect::from_xywh(rect.x, rect.y, rect.w, rect.h)
+            .ok_or("Invalid rectangle")?);  Ok(())
     }
 
-    pixmap.stroke_path(&pb.finish().ok_or("Fail build path from segments")?,
-        paint, stroke, trfm, None);     Ok(())
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.push(paint, None)
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.push(paint, Some(width))
+    }
+    fn clear (&mut self) { self.pb.clear() }
 }
 
-fn segment_to_path(seg: &Segment, pb: &mut skia::PathBuilder) -> bool {
-    pb.move_to(seg.start.x, seg.start.y);
-    let mut change_lw = false;
+/// Debug marks of the command at `index` in image coordinates: its bounding box,
+/// segment starts with the next distinct point (for the direction), Bézier handles
+/// as (anchor, control point) pairs, and arc centers. Collected as a `Canvas`
+/// following the path of the command.
+struct Marks { index: usize, bbox: Option<skia::Rect>, starts: Vec<(skia::Point, skia::Point)>,
+    handles: Vec<(skia::Point, skia::Point)>, centers: Vec<skia::Point>,
+    last: skia::Point, filled: bool }
+
+impl Marks {
+    fn new<R: io::Read, W: io::Write>(index: usize, img: &TinyVG<R, W>, cmd: &Command) -> Self {
+        let mut marks = Self { index, bbox: cmd.bounds().and_then(|r|
+            skia::Rect::from_xywh(r.x, r.y, r.w, r.h)), starts: vec![], handles: vec![],
+            centers: vec![], last: skia::Point::zero(), filled: false };
+        let _ = marks.draw_command(img, cmd);   marks
+    }
+
+    /// Point the direction of the last start to `pt`, if not yet.
+    fn toward(&mut self, pt: skia::Point) {
+        if let Some(start) = self.starts.last_mut().filter(|start| start.0 == start.1) {
+            start.1 = pt }
+    }
+}
+
+/// After filling, the path is just built again for stroking (in segments).
+impl Canvas for Marks {
+    type Paint = ();    type Error = ();
+
+    fn paint<R: io::Read, W: io::Write>(&mut self, _: &TinyVG<R, W>,
+        _: &Style) -> Result<Self::Paint, Self::Error> { Ok(()) }
+
+    fn move_to (&mut self, pt: Point) {
+        let pt = pt.into();     // not where a segment is split by line widths
+        if !self.filled && (self.starts.is_empty() || pt != self.last) {
+            self.starts.push((pt, pt)) }   self.last = pt;
+    }
+    fn line_to (&mut self, pt: Point) {
+        let pt = pt.into();     if !self.filled { self.toward(pt) }   self.last = pt;
+    }
+    fn quad_to (&mut self, ctrl: Point, end: Point) {
+        let (ctrl, end) = (ctrl.into(), end.into());
+        if !self.filled {   self.toward(ctrl);  self.toward(end);
+            self.handles.extend([(self.last, ctrl), (end, ctrl)]);
+        }   self.last = end;
+    }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        let (c0, c1, end) = (ctrl.0.into(), ctrl.1.into(), end.into());
+        if !self.filled {   self.toward(c0);    self.toward(c1);    self.toward(end);
+            self.handles.extend([(self.last, c0), (end, c1)]);
+        }   self.last = end;
+    }
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        use kurbo::Shape;
+        if let Some(arc) = kurbo::Arc::from_svg_arc(&svg_arc(start,
+            radii, rotation, large, sweep, end)).filter(|_| !self.filled) {
+            self.centers.push(skia::Point::from_xy(arc.center.x as _, arc.center.y as _));
+            if let Some(kurbo::PathEl::CurveTo(p1, ..)) = arc.path_elements(0.1).nth(1) {
+                self.toward(skia::Point::from_xy(p1.x as _, p1.y as _)) }   // the tangent
+        }   self.line_to(end);  Ok(())
+    }
+
+    fn close(&mut self) {}
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        self.move_to(Point { x: rect.x, y: rect.y });
+        self.line_to(Point { x: rect.x + rect.w, y: rect.y });  Ok(())
+    }
 
-    for cmd in &seg.cmds {
-        if cmd.lwidth.is_some() { change_lw = true }
-        process_segcmd(pb, &cmd.instr);
-    }   change_lw
+    fn fill  (&mut self, _: &Self::Paint) -> Result<(), Self::Error> {
+        self.filled = true;     Ok(())
+    }
+    fn stroke(&mut self, _: &Self::Paint, _: f32) -> Result<(), Self::Error> { Ok(()) }
+    fn clear (&mut self) {}
 }
 
-fn process_segcmd(pb: &mut skia::PathBuilder, cmd: &SegInstr) {
-    match cmd {     SegInstr::ClosePath => pb.close(),
-        SegInstr::Line  { end } => pb.line_to(end.x, end.y),
-        SegInstr::HLine { x }     => pb.line_to(*x, pb.last_point().unwrap().y),
-        SegInstr::VLine { y }     => pb.line_to(pb.last_point().unwrap().x, *y),
+impl PreparedScene {
+    pub fn new<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
+        opts: &RenderOptions) -> Result<Self, &'static str> {
+        Self::with_filter(img, opts, &|_, _| true)
+    }
+
+    /// Prepare just the commands for which `filter` with their index returns true.
+    pub fn with_filter<R: io::Read, W: io::Write>(img: &TinyVG<R, W>, opts: &RenderOptions,
+        filter: &dyn Fn(usize, &Command) -> bool) -> Result<Self, &'static str> {
+        let mut canvas = SceneBuilder { opts, pb: skia::PathBuilder::new(),
+            index: 0, items: vec![] };
+        let mut marks = vec![];
+
+        let visible = opts.layer_mask(img);
+        for (index, cmd) in img.commands.iter().enumerate().zip(visible)
+            .filter_map(|(cmd, show)| show.then_some(cmd)).filter(|(i, cmd)| filter(*i, cmd)) {
+            canvas.index = index;   canvas.draw_command(img, cmd)?;
+            if opts.debug { marks.push(Marks::new(index, img, cmd)) }
+        }   Ok(Self { width: img.header.width, height: img.header.height,
+            items: canvas.items, marks })
+    }
+
+    pub fn render_with(&self, opts: &RenderOptions) -> Result<skia::Pixmap, &'static str> {
+        self.render_timing(opts, None)
+    }
+
+    /// Render single-threaded, recording the drawing time of each command into `times`.
+    pub fn render_timed(&self, opts: &RenderOptions,
+        times: &mut Vec<(usize, Duration)>) -> Result<skia::Pixmap, &'static str> {
+        self.render_timing(opts, Some(times))
+    }
+
+    fn render_timing(&self, opts: &RenderOptions,
+        times: Option<&mut Vec<(usize, Duration)>>) -> Result<skia::Pixmap, &'static str> {
+        let (.., trfm) = opts.layout(self.width, self.height);
+        let err_msg = "Fail to create pixmap";  let n = opts.samples();
+        let (width, height, strfm, rows) = self.extent(opts, n);
+        let mut pixmap = skia::Pixmap::new(width, height).ok_or(err_msg)?;
+        if let Some(color) = opts.background { pixmap.fill(color.into()) }
+
+        let hairline = min_line_width(&trfm);   // of output pixels, even if supersampled
+        if let Some(times) = times {
+            for group in self.items.chunk_by(|a, b| a.index == b.index) {
+                let tnow = Instant::now();
+                Self::draw(group, &mut pixmap.as_mut(), strfm, None, hairline, rows.clone());
+                times.push((group[0].index, tnow.elapsed()));
+            }
+        } else if opts.threads == 1 {
+            Self::draw(&self.items, &mut pixmap.as_mut(), strfm, None, hairline, rows);
+        } else { self.draw_bands(&mut pixmap, strfm, hairline, opts.threads)?; }
+
+        if 1 < n { pixmap = skia::Pixmap::from_vec(downsample(opts, pixmap.data(), width,
+            (width * 4) as _, true), skia::IntSize::from_wh(width / n, height / n)
+            .ok_or(err_msg)?).ok_or(err_msg)?; }
+        if let Some(rect) = opts.region { pixmap = pixmap.clone_rect(rect).ok_or(err_msg)? }
+        self.draw_marks(&mut pixmap.as_mut(), trfm, None, None);    Ok(pixmap)
+    }
+
+    /// Supersampled size of the pixmap to draw into by the returned transform of the whole
+    /// output, and the rows to draw. A `region` reproduces the pixels of the whole output
+    /// exactly, by the same transform and clipping: its pixmap spans from the output's
+    /// origin past the region as far as the paths crossing it and the filter reach.
+    /// XXX: costs the memory of the output above and left of the region too.
+    fn extent(&self, opts: &RenderOptions, n: u32) -> (u32, u32, skia::Transform, Range<u32>) {
+        let (width, height, trfm) = opts.full_layout(self.width, self.height);
+        let (strfm, hairline) = (trfm.post_scale(n as _, n as _), min_line_width(&trfm));
+        let Some(rect) = opts.region else { return (width * n, height * n, strfm, 0..height * n) };
+
+        let reach = if 1 < n { 4 } else { 1 } as f32 * n as f32;   // of Lanczos, or AA fringe
+        let (mut right, mut bottom) = ((rect.right() as u32 * n) as f32 + reach,
+            (rect.bottom() as u32 * n) as f32 + reach);
+        let area = skia::Rect::from_ltrb((rect.left() as u32 * n) as f32 - reach,
+            (rect.top() as u32 * n) as f32 - reach, right, bottom);
+        for rect in self.items.iter().filter_map(|item| item.bounds(hairline)
+            .and_then(|rect| rect.transform(strfm))).filter(|rect|
+            area.is_some_and(|area| area.intersect(rect).is_some())) {
+            right = right.max(rect.right() + 1.0);   bottom = bottom.max(rect.bottom() + 1.0);
+        }
+
+        let clamp = |v: f32, max: u32| ((v.ceil() as u32).div_ceil(n) * n).min(max * n);
+        let (right, bottom) = (clamp(right, width), clamp(bottom, height));
+        let top = (rect.top() as u32 * n).saturating_sub(reach as u32);
+        (right, bottom, strfm, top..bottom)
+    }
+
+    /// Frames of the image built up one command at a time, each with the current command
+    /// highlighted by a translucent `highlight` fill and outline (and its debug marks),
+    /// for a step-through animation (see `save_apng`). The `threads` option is ignored.
+    pub fn build_up<'a>(&'a self, opts: &'a RenderOptions, highlight: RGBA8888)
+        -> Result<impl ExactSizeIterator<Item = skia::Pixmap> + 'a, &'static str> {
+        let (width, height, trfm) = opts.layout(self.width, self.height);
+        let n = opts.samples();
+        let mut base = skia::Pixmap::new(width * n, height * n).ok_or("Fail to create pixmap")?;
+        if let Some(color) = opts.background { base.fill(color.into()) }
+
+        let hairline = min_line_width(&trfm);
+        let strfm = trfm.post_scale(n as _, n as _);
+        let mut paint = skia::Paint { anti_alias: opts.anti_alias, ..Default::default() };
+        let stroke = skia::Stroke { width: hairline * 1.5, ..Default::default() };
+        let groups = self.items.chunk_by(|a, b| a.index == b.index);
+
+        Ok(groups.collect::<Vec<_>>().into_iter().map(move |group| {
+            Self::draw(group, &mut base.as_mut(), strfm, None, hairline, 0..height * n);
+            let mut frame = base.clone();
+
+            for item in group {
+                paint.set_color_rgba8(highlight.r, highlight.g, highlight.b, highlight.a / 3);
+                if item.lwidth.is_none() { frame.fill_path(&item.path, &paint,
+                    skia::FillRule::Winding, strfm, None) }
+                paint.set_color_rgba8(highlight.r, highlight.g, highlight.b, highlight.a);
+                frame.stroke_path(&item.path, &paint, &stroke, strfm, None);
+            }
+
+            if 1 < n { frame = skia::IntSize::from_wh(width, height).and_then(|size|
+                skia::Pixmap::from_vec(downsample(opts, frame.data(), width * n,
+                    (width * n * 4) as _, true), size)).unwrap_or(frame) }
+            self.draw_marks(&mut frame.as_mut(), trfm, None, Some(group[0].index));    frame
+        }))
+    }
+
+    /// Same as `Render::render_into`.
+    pub fn render_into(&self, pixmap: &mut skia::PixmapMut, trfm: skia::Transform,
+        mask: Option<&skia::Mask>, opacity: f32) -> Result<(), &'static str> {
+        let hairline = min_line_width(&trfm);
+        if 1.0 <= opacity {
+            Self::draw(&self.items, pixmap, trfm, mask, hairline, 0..pixmap.height());
+            self.draw_marks(pixmap, trfm, mask, None);  return Ok(())
+        }
+        if opacity <= 0.0 { return Ok(()) }
+
+        // group opacity needs an intermediate layer, just covering the drawn area
+        let Some(bbox) = self.items.iter().filter_map(|item| item.bounds(hairline))
+            .reduce(|bbox, rect| bbox.join(&rect).unwrap_or(bbox)) else { return Ok(()) };
+        let Some(bbox) = bbox.transform(trfm).and_then(|bbox| bbox.round_out())
+            .and_then(|bbox| bbox.intersect(&skia::IntRect::from_xywh(0, 0,
+                pixmap.width(), pixmap.height())?)) else { return Ok(()) };
+
+        let mut layer = skia::Pixmap::new(bbox.width(), bbox.height())
+            .ok_or("Fail to create pixmap")?;
+        Self::draw(&self.items, &mut layer.as_mut(), trfm.post_translate(-bbox.x() as f32,
+            -bbox.y() as f32), None, hairline, 0..bbox.height());
+        pixmap.draw_pixmap(bbox.x(), bbox.y(), layer.as_ref(), &skia::PixmapPaint {
+            opacity, ..Default::default() }, skia::Transform::identity(), mask);
+        self.draw_marks(pixmap, trfm, mask, None);  Ok(())
+    }
+
+    /// Render in horizontal bands on a pool of `threads`, pixel-identical to single-threaded:
+    /// each band draws the paths intersecting it by the same transform into a scratch of the
+    /// whole size (so edges are clipped the same), and copies just its rows back.
+    /// XXX: a scratch pixmap of the image size per band.
+    fn draw_bands(&self, pixmap: &mut skia::Pixmap, trfm: skia::Transform,
+        hairline: f32, threads: usize) -> Result<(), &'static str> {
+        use rayon::prelude::*;
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads)
+            .build().map_err(|_| "Fail to create thread pool")?;
+        let (width, height) = (pixmap.width(), pixmap.height());
+        let rows = height.div_ceil(pool.current_num_threads() as u32).max(1);
+        let stride = width as usize * 4;
+
+        pool.install(|| pixmap.data_mut().par_chunks_mut(rows as usize * stride).enumerate()
+            .try_for_each(|(i, band)| {
+                let (top, count) = (i as u32 * rows, (band.len() / stride) as u32);
+                let range = top as usize * stride..(top + count) as usize * stride;
+                let mut scratch = skia::Pixmap::new(width, height)
+                    .ok_or("Fail to create pixmap")?;
+                scratch.data_mut()[range.clone()].copy_from_slice(band);
+
+                Self::draw(&self.items, &mut scratch.as_mut(), trfm, None, hairline,
+                    top..top + count);
+                band.copy_from_slice(&scratch.data()[range]);   Ok(())
+            }))
+    }
 
-        SegInstr::CubicBezier { ctrl, end } =>
-            pb.cubic_to(ctrl.0.x, ctrl.0.y, ctrl.1.x, ctrl.1.y, end.x, end.y),
-        SegInstr::ArcCircle  { large, sweep, radius, end } =>
-            pb.arc_to((*radius, *radius), 0.0, *large, *sweep, *end),
+    /// Draw the paths intersecting the pixel `rows`, culling the others by their bounds.
+    fn draw(items: &[Item], pixmap: &mut skia::PixmapMut, trfm: skia::Transform,
+        mask: Option<&skia::Mask>, hairline: f32, rows: Range<u32>) {
+        let mut stroke = skia::Stroke { line_join: skia::LineJoin::Round,
+            line_cap: skia::LineCap::Round, ..Default::default() };
+        let (right, top, bottom) = (pixmap.width() as f32 + 1.0,    // with AA fringe
+            rows.start as f32 - 1.0, rows.end as f32 + 1.0);
+
+        for item in items {
+            if item.bounds(hairline).and_then(|rect| rect.transform(trfm)).is_some_and(|rect|
+                right <= rect.left() || rect.right() <= -1.0 ||
+                bottom <= rect.top() || rect.bottom() <= top) { continue }
+
+            match item.lwidth {
+                None => pixmap.fill_path(&item.path, &item.paint,
+                    skia::FillRule::Winding, trfm, mask),
+                Some(lwidth) => {   stroke.width = lwidth.max(hairline);
+                    pixmap.stroke_path(&item.path, &item.paint, &stroke, trfm, mask);
+                }
+            }
+        }
+    }   // rasterize
+
+    /// Draw the debug marks (if prepared) in output pixels, just of the command at `only`
+    /// if any, so they stay thin and legible at any scale.
+    fn draw_marks(&self, pixmap: &mut skia::PixmapMut, trfm: skia::Transform,
+        mask: Option<&skia::Mask>, only: Option<usize>) {
+        if self.marks.is_empty() { return }
+        let color = |r, g, b| { let mut paint = skia::Paint::default();
+            paint.set_color_rgba8(r, g, b, 255);    paint };
+        let (outline, handle, start) = (color(0, 160, 255), color(255, 128, 0), color(0, 192, 0));
+        let (center, bbox, label) = (color(255, 0, 0), color(255, 0, 255), color(255, 255, 255));
+        let (id, hairline) = (skia::Transform::identity(), skia::Stroke::default());
+        let map = |mut pt: skia::Point| { trfm.map_points(std::slice::from_mut(&mut pt)); pt };
+        let shown = |index| only.is_none_or(|only| only == index);
+
+        for item in self.items.iter().filter(|item| shown(item.index)) {
+            if let Some(path) = item.path.clone().transform(trfm) {
+                pixmap.stroke_path(&path, &outline, &hairline, id, mask) }
+        }
+
+        for marks in self.marks.iter().filter(|marks| shown(marks.index)) {
+            let (mut lines, mut dots, mut boxes) = (skia::PathBuilder::new(),
+                skia::PathBuilder::new(), skia::PathBuilder::new());
+            for (anchor, ctrl) in marks.handles.iter().map(|&(p0, p1)| (map(p0), map(p1))) {
+                lines.move_to(anchor.x, anchor.y);  lines.line_to(ctrl.x, ctrl.y);
+                if let Some(rect) = skia::Rect::from_xywh(ctrl.x - 2.0, ctrl.y - 2.0,
+                    4.0, 4.0) { boxes.push_rect(rect) }
+            }
+            if let Some(path) = lines.finish() {
+                pixmap.stroke_path(&path, &handle, &hairline, id, mask) }
+            if let Some(path) = boxes.finish() {
+                pixmap.fill_path(&path, &handle, skia::FillRule::Winding, id, mask) }
+
+            let mut arrows = skia::PathBuilder::new();
+            for (p0, p1) in marks.starts.iter().map(|&(p0, p1)| (map(p0), map(p1))) {
+                dots.push_circle(p0.x, p0.y, 2.5);
+                let mut dir = p1 - p0;  if !dir.normalize() { continue }
+                let at = |len: f32| skia::Point::from_xy(p0.x + dir.x * len, p0.y + dir.y * len);
+                let (tip, base, wing) = (at(12.0), at(7.0),
+                    skia::Point::from_xy(-dir.y * 3.5, dir.x * 3.5));
+                arrows.move_to(p0.x, p0.y);     arrows.line_to(base.x, base.y);
+                dots.move_to(tip.x, tip.y);     dots.line_to((base + wing).x, (base + wing).y);
+                dots.line_to((base - wing).x, (base - wing).y);     dots.close();
+            }
+            if let Some(path) = arrows.finish() {
+                pixmap.stroke_path(&path, &start, &hairline, id, mask) }
+            if let Some(path) = dots.finish() {
+                pixmap.fill_path(&path, &start, skia::FillRule::Winding, id, mask) }
+
+            let mut cross = skia::PathBuilder::new();
+            for pt in marks.centers.iter().map(|&pt| map(pt)) {
+                cross.move_to(pt.x - 4.0, pt.y);   cross.line_to(pt.x + 4.0, pt.y);
+                cross.move_to(pt.x, pt.y - 4.0);   cross.line_to(pt.x, pt.y + 4.0);
+            }
+            if let Some(path) = cross.finish() {
+                pixmap.stroke_path(&path, &center, &hairline, id, mask) }
 
-        SegInstr::ArcEllipse { large, sweep, radii, rotation, end } =>
-                pb.arc_to(*radii, *rotation, *large, *sweep, *end),
+            let Some(rect) = marks.bbox.and_then(|rect| rect.transform(trfm)) else { continue };
+            pixmap.stroke_path(&skia::PathBuilder::from_rect(rect), &bbox, &hairline, id, mask);
+            draw_label(pixmap, rect.left(), rect.top(), marks.index, (&bbox, &label), mask);
+        }
+    }
+}
 
-        SegInstr::QuadBezier { ctrl, end } =>
-            pb.quad_to(ctrl.x, ctrl.y, end.x, end.y),
+/// Draw the decimal `number` with a 3×5 pixel font (each font pixel 2×2) on a box,
+/// just above (`x`, `y`) if there is room, kept inside the pixmap.
+fn draw_label(pixmap: &mut skia::PixmapMut, x: f32, y: f32, number: usize,
+    (bg, fg): (&skia::Paint, &skia::Paint), mask: Option<&skia::Mask>) {
+    const DIGITS: [u16; 10] = [0b111_101_101_101_111, 0b010_110_010_010_111,
+        0b111_001_111_100_111, 0b111_001_111_001_111, 0b101_101_111_001_001,
+        0b111_100_111_001_111, 0b111_100_111_101_111, 0b111_001_001_001_001,
+        0b111_101_111_101_111, 0b111_101_111_001_111];     // rows of 3 bits, top first
+    let (text, cell, id) = (number.to_string(), 2.0, skia::Transform::identity());
+    let (w, h) = ((text.len() * 4 + 1) as f32 * cell, 7.0 * cell);
+
+    let x = x.round().min(pixmap.width() as f32 - w).max(0.0);
+    let y = if h <= y { y.round() - h } else { y.round().max(0.0) }
+        .min(pixmap.height() as f32 - h).max(0.0);
+    let Some(rect) = skia::Rect::from_xywh(x, y, w, h) else { return };
+    pixmap.fill_rect(rect, bg, id, mask);
+
+    for (i, digit) in text.bytes().enumerate() {
+        let bits = DIGITS[(digit - b'0') as usize];
+        for (row, col) in (0..5).flat_map(|row| (0..3).map(move |col| (row, col))) {
+            if bits >> (14 - row * 3 - col) & 1 == 0 { continue }
+            let Some(rect) = skia::Rect::from_xywh(x + (i * 4 + col + 1) as f32 * cell,
+                y + (row + 1) as f32 * cell, cell, cell) else { continue };
+            pixmap.fill_rect(rect, fg, id, mask);
+        }
     }
 }
 
 #[allow(non_local_definitions)]
 fn style_to_paint<'a, R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
-    style: &Style, trfm: skia::Transform) ->
-    Result<skia::Paint<'a>, &'static str> {
+    style: &Style, opts: &RenderOptions) -> Result<skia::Paint<'a>, &'static str> {
+    // the shader is transformed along with the path by fill_path/stroke_path
     impl From<RGBA8888> for skia::Color {  // XXX: why not use ColorU8 defaultly in skia?
         fn from(c: RGBA8888) -> Self { Self::from_rgba8(c.r, c.g, c.b, c.a) }
     }
@@ -173,60 +741,145 @@ fn style_to_paint<'a, R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
         //fn from(pt: Point) -> Self { unsafe { std::mem::transmute(pt) } }
     }
 
-    let mut paint = skia::Paint::default(); // default BlendMode::SourceOver
-    match style {   // paint.anti_alias is default true
+    let stops = |cindex: &(u32, u32)| gradient_stops(img.lookup_color(cindex.0),
+        img.lookup_color(cindex.1), opts.srgb_gradient).into_iter().map(|(pos, color)|
+            skia::GradientStop::new(pos, color.into())).collect();
+
+    let mut paint = skia::Paint {   // default BlendMode::SourceOver
+        anti_alias: opts.anti_alias, ..Default::default() };
+    match style {
         Style::FlatColor(idx) => paint.set_color(img.lookup_color(*idx).into()),
 
         Style::LinearGradient { points, cindex } => {
             paint.shader = skia::LinearGradient::new(points.0.into(), points.1.into(),
-                vec![ skia::GradientStop::new(0.0, img.lookup_color(cindex.0).into()),
-                      skia::GradientStop::new(1.0, img.lookup_color(cindex.1).into()),
-                ],    skia::SpreadMode::Pad, trfm)
+                stops(cindex), skia::SpreadMode::Pad, skia::Transform::identity())
                 .ok_or("Fail to create linear gradient shader")?;  //paint.anti_alias = false;
         }
         Style::RadialGradient { points, cindex } => {
             paint.shader = skia::RadialGradient::new(points.0.into(), 0.0, points.0.into(),
                     (points.1.x - points.0.x) .hypot(points.1.y - points.0.y),
-                vec![ skia::GradientStop::new(0.0, img.lookup_color(cindex.0).into()),
-                      skia::GradientStop::new(1.0, img.lookup_color(cindex.1).into()),
-                ],    skia::SpreadMode::Pad, trfm)
+                stops(cindex), skia::SpreadMode::Pad, skia::Transform::identity())
                 .ok_or("Fail to create radial gradient shader")?;  //paint.anti_alias = false;
         }
     }   Ok(paint)
 }
 
-trait PathBuilderExt {
-    fn arc_to(&mut self, radius: (f32, f32), rotation: f32,
-        large: bool, sweep: bool, target: Point);
-}   // https://github.com/RazrFalcon/resvg/blob/master/crates/usvg/src/parser/shapes.rs#L287
+/// Write `frames` of the same size as an endlessly looping APNG, each shown `delay` ms.
+pub fn save_apng<P: AsRef<std::path::Path>>(frames: impl ExactSizeIterator<Item = skia::Pixmap>,
+    path: P, delay: u16) -> io::Result<()> {
+    let mut frames = frames.peekable();
+    let Some(first) = frames.peek() else { return Err(io::Error::new(
+        io::ErrorKind::InvalidInput, "no frames to save")) };
+
+    let mut encoder = png::Encoder::new(io::BufWriter::new(
+        std::fs::File::create(path)?), first.width(), first.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len() as _, 0)?;
+    encoder.set_frame_delay(delay, 1000)?;
+
+    let mut writer = encoder.write_header()?;
+    for frame in frames {   // demultiply to straight alpha like `Pixmap::encode_png`
+        let data = frame.pixels().iter().flat_map(|pixel| { let c = pixel.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()] }).collect::<Vec<_>>();
+        writer.write_image_data(&data)?;
+    }   writer.finish()?;   Ok(())
+}
 
-//  https://medium.com/@Ovilia/if-you-wish-to-draw-an-arc-in-a-web-page-then-you-should-probably-use-canvas-or-svg-5a81194bbdf7
-//  https://stackoverflow.com/questions/6729056/mapping-svg-arcto-to-html-canvas-arcto
-//  https://spencermortensen.com/articles/bezier-circle/
+/// Minimal line width in image units to cover at least one device pixel under `trfm`,
+/// as the spec requires, i.e. the reciprocal of its smallest singular value.
+pub fn min_line_width(trfm: &skia::Transform) -> f32 {
+    let det = trfm.sx * trfm.sy - trfm.kx * trfm.ky;
+    let sum = trfm.sx * trfm.sx + trfm.kx * trfm.kx + trfm.ky * trfm.ky + trfm.sy * trfm.sy;
+    let smin = ((sum - (sum * sum - 4.0 * det * det).max(0.0).sqrt()) / 2.0).sqrt();
+    if 0.0 < smin { 1.0 / smin } else { 0.0 }
+}
 
-//  SVG arc to Canvas arc: https://github.com/nical/lyon/blob/main/crates/geom/src/arc.rs
-impl PathBuilderExt for skia::PathBuilder {
-    fn arc_to(&mut self, radii: (f32, f32), rotation: f32,
-        large: bool, sweep: bool, end: Point) {
-        let prev = self.last_point().unwrap_or_default();
+/// Downsample RGBA `data` (rows `stride` bytes apart, `width` pixels) rendered at
+/// `opts.samples()` times the size, by `opts.filter` and in linear light if `opts.gamma_correct`.
+/// Color is premultiplied by alpha if `premul`, both in `data` and the tightly packed result.
+pub fn downsample(opts: &RenderOptions, data: &[u8], width: u32, stride: usize,
+    premul: bool) -> Vec<u8> {
+    let (n, linear) = (opts.samples() as usize, opts.gamma_correct);
+    let (sw, sh) = (width as usize, data.len() / stride);
+    let (dw, dh) = (sw / n, sh / n);
+
+    let decode = |px: &[u8]| {  let a = px[3] as f32 / 255.0;
+        let c = [px[0], px[1], px[2]].map(|v| {     let v = v as f32 / 255.0;
+            let v = if !premul { v } else if 0.0 < a { v / a } else { 0.0 };
+            if linear { map_to_linear(v.min(1.0)) * a } else { v * a }
+        }); [c[0], c[1], c[2], a]
+    };
+    let encode = |px: &[f32; 4], out: &mut Vec<u8>| {
+        let a = (px[3].clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        let c = [px[0], px[1], px[2]].map(|v| {
+            let v = if 0.0 < px[3] { (v / px[3]).clamp(0.0, 1.0) } else { 0.0 };
+            let v = if linear { map_to_gamma(v) } else { v };
+            if premul { ((v * a as f32 + 0.5) as u8).min(a) } else { (v * 255.0 + 0.5) as _ }
+        }); out.extend([c[0], c[1], c[2], a]);
+    };
+
+    let (hw, vw) = (filter_weights(opts.filter, n, sw, dw),
+                    filter_weights(opts.filter, n, sh, dh));
+    let mut rows = Vec::with_capacity(dw * sh);
+    for row in data.chunks(stride).take(sh) {
+        let row = row[..sw * 4].chunks_exact(4).map(decode).collect::<Vec<_>>();
+        rows.extend(hw.iter().map(|taps| taps.iter().fold([0.0; 4], |mut acc, &(i, w)| {
+            acc.iter_mut().zip(row[i]).for_each(|(acc, v)| *acc += v * w);  acc })));
+    }
 
-        let svg_arc = kurbo::SvgArc {
-               to: kurbo::Point::new(end.x as _, end.y as _),
-             from: kurbo::Point::new(prev.x as _, prev.y as _),
-            radii: kurbo::Vec2 ::new(radii.0 as _, radii.1 as _),
-            x_rotation: (rotation as f64).to_radians(), large_arc: large, sweep,
-        };
+    let mut out = Vec::with_capacity(dw * dh * 4);
+    for taps in &vw { for x in 0..dw {
+        encode(&taps.iter().fold([0.0; 4], |mut acc, &(y, w)| {
+            acc.iter_mut().zip(rows[y * dw + x]).for_each(|(acc, v)| *acc += v * w);  acc
+        }), &mut out);
+    } }     out
+}
 
-        if let Some(arc) = kurbo::Arc::from_svg_arc(&svg_arc) {
-            arc.to_cubic_beziers(0.1, |p1, p2, end|
-                 self.cubic_to(p1.x as _, p1.y as _,
-                               p2.x as _, p2.y as _, end.x as _, end.y as _))
-        } else { self.line_to(end.x, end.y) }
-    }
+/// Normalized taps (source index, weight) of each of `dst` output pixels along an axis.
+fn filter_weights(filter: Filter, n: usize, src: usize, dst: usize) -> Vec<Vec<(usize, f32)>> {
+    const LOBES: f32 = 3.0;
+    let sinc = |x: f32| if x.abs() < 1e-6 { 1.0 } else {
+        let x = x * std::f32::consts::PI;   x.sin() / x };
+
+    (0..dst).map(|i| {  let taps = match filter {
+        Filter::Box => (i * n..(i + 1) * n).map(|j| (j, 1.0)).collect::<Vec<_>>(),
+        Filter::Lanczos => {    let (center, n) = ((i as f32 + 0.5) * n as f32, n as f32);
+            let start = (center - LOBES * n).floor().max(0.0) as usize;
+            let end = ((center + LOBES * n).ceil() as usize).min(src);
+            (start..end).map(|j| { let x = (j as f32 + 0.5 - center) / n;
+                (j, if x.abs() < LOBES { sinc(x) * sinc(x / LOBES) } else { 0.0 })
+            }).collect()
+        }
+    };  let sum = taps.iter().map(|(_, w)| w).sum::<f32>();
+        taps.into_iter().map(|(j, w)| (j, w / sum)).collect()
+    }).collect()
+}
+
+/// Stops of a gradient from `c0` to `c1`. The spec requires interpolation in linear
+/// color space, approximated by piecewise linear stops in sRGB, unless `srgb` (legacy).
+pub fn gradient_stops(c0: RGBA8888, c1: RGBA8888, srgb: bool) -> Vec<(f32, RGBA8888)> {
+    let mut stops = vec![(0.0, c0)];
+    if srgb { stops.push((1.0, c1)) } else { subdivide(c0, c1, (0.0, 1.0), 8, &mut stops) }
+    stops
+}
+
+/// Split the range until the stops are off by at most 1 from the exact midpoint.
+fn subdivide(c0: RGBA8888, c1: RGBA8888, range: (f32, f32), depth: u32,
+    stops: &mut Vec<(f32, RGBA8888)>) {
+    let (s0, s1) = (lerp_srgb(c0, c1, range.0), lerp_srgb(c0, c1, range.1));
+    let (fm, exact) = ((range.0 + range.1) / 2.0, lerp_srgb(c0, c1, (range.0 + range.1) / 2.0));
+    let off = |a: u8, b: u8, c: u8| 1 < ((a as i32 + b as i32) / 2 - c as i32).abs();
+
+    if 0 < depth && (off(s0.r, s1.r, exact.r) || off(s0.g, s1.g, exact.g) ||
+                     off(s0.b, s1.b, exact.b) || off(s0.a, s1.a, exact.a)) {
+        subdivide(c0, c1, (range.0, fm), depth - 1, stops);
+        subdivide(c0, c1, (fm, range.1), depth - 1, stops);
+    } else { stops.push((range.1, s1)) }
 }
 
 // https://github.com/TinyVG/sdk/blob/ed01b9112/src/lib/rendering.zig#L1133
-/* fn lerp_sRGB(c0: RGBA8888, c1: RGBA8888, f_unchecked: f32) -> RGBA8888 { // blend
+fn lerp_srgb(c0: RGBA8888, c1: RGBA8888, f_unchecked: f32) -> RGBA8888 { // blend
     //  Color interpolation is needed in gradients and must performed in linear
     //  color space. This means that the value from the color table needs to be
     //  converted to linear color space, then each color component is
@@ -236,18 +889,18 @@ impl PathBuilderExt for skia::PathBuilder {
         r: linear2gamma(lerp(gamma2linear(c0.r), gamma2linear(c1.r), f)),
         g: linear2gamma(lerp(gamma2linear(c0.g), gamma2linear(c1.g), f)),
         b: linear2gamma(lerp(gamma2linear(c0.b), gamma2linear(c1.b), f)),
-        a: (lerp(c0.a as f32 / 255.0, c1.a as f32 / 255.0, f) * 255.0) as _,
+        a: (lerp(c0.a as f32 / 255.0, c1.a as f32 / 255.0, f) * 255.0 + 0.5) as _,
     }
 }
 
-fn lerp_value(src: f32, dst: f32, src_alpha: f32, dst_alpha: f32, fin_alpha: f32) -> f32 {
+/* fn lerp_value(src: f32, dst: f32, src_alpha: f32, dst_alpha: f32, fin_alpha: f32) -> f32 {
     mapToGamma((1.0 / fin_alpha) * (src_alpha * mapToLinear(src) +
                (1.0 - src_alpha) *  dst_alpha * mapToLinear(dst)))  // Alpha Blending
-}
-
-const sRGB_gamma: f32 = 2.2;
-#[inline] fn  mapToLinear(v: f32) -> f32 { f32::powf(v, sRGB_gamma) }
-#[inline] fn  mapToGamma (v: f32) -> f32 { f32::powf(v, 1.0 / sRGB_gamma) }
-#[inline] fn linear2gamma(v: f32) ->  u8 { (255.0 * mapToGamma(v)) as _ }
-#[inline] fn gamma2linear(v:  u8) -> f32 { mapToLinear(v as f32 / 255.0) }
-#[inline] fn lerp(a: f32, b: f32, f: f32) -> f32 { a + (b - a) * f } */
+} */
+
+const SRGB_GAMMA: f32 = 2.2;
+#[inline] fn map_to_linear(v: f32) -> f32 { f32::powf(v, SRGB_GAMMA) }
+#[inline] fn map_to_gamma (v: f32) -> f32 { f32::powf(v, 1.0 / SRGB_GAMMA) }
+#[inline] fn linear2gamma(v: f32) ->  u8 { (255.0 * map_to_gamma(v) + 0.5) as _ }
+#[inline] fn gamma2linear(v:  u8) -> f32 { map_to_linear(v as f32 / 255.0) }
+#[inline] fn lerp(a: f32, b: f32, f: f32) -> f32 { a + (b - a) * f }
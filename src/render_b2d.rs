@@ -6,15 +6,41 @@
  ****************************************************************/
 
 use crate::tinyvg::*;
-use crate::blend2d::*;
-use std::{io, result::Result};
+use crate::{blend2d::*, canvas::Canvas, render::{RenderOptions, Profile,
+    downsample, gradient_stops, min_line_width}};
+use std::{io, result::Result, time::{Duration, Instant}};
 
-pub trait Render { fn render(&self, scale: f32) -> Result<BLImage, BLErr>; }
+pub trait Render {
+    fn render(&self, scale: f32) -> Result<BLImage, BLErr> {
+        self.render_with(&RenderOptions { scale, ..Default::default() })
+    }
+    /// Render with only the `visible` named layers switched on, or all if `None`.
+    fn render_layers(&self, scale: f32, visible: Option<&[&str]>) -> Result<BLImage, BLErr> {
+        self.render_with(&RenderOptions { scale, layers: visible.map(|names|
+            names.iter().map(|name| name.to_string()).collect()), ..Default::default() })
+    }
+    fn render_with(&self, opts: &RenderOptions) -> Result<BLImage, BLErr> {
+        self.render_timed(opts, None)
+    }
+    /// Render, recording the drawing time of each drawn command into `times` if any.
+    fn render_timed(&self, opts: &RenderOptions,
+        times: Option<&mut Vec<(usize, Duration)>>) -> Result<BLImage, BLErr>;
+    /// Render single-threaded and profile each command, see `render::Profile`.
+    fn profile(&self, opts: &RenderOptions) -> Result<(BLImage, Profile), BLErr>;
+}
 
 impl<R: io::Read, W: io::Write> Render for TinyVG<R, W> {
-    fn render(&self, scale: f32) -> Result<BLImage, BLErr> {
-        let width  = (self.header.width  as f32 * scale).ceil() as _;
-        let height = (self.header.height as f32 * scale).ceil() as _;
+    fn profile(&self, opts: &RenderOptions) -> Result<(BLImage, Profile), BLErr> {
+        let (opts, mut times) = (RenderOptions { threads: 1, ..opts.clone() }, vec![]);
+        let tnow = Instant::now();
+        let img = self.render_timed(&opts, Some(&mut times))?;
+        let profile = Profile::new(self, &opts, "Blend2D", &times, tnow.elapsed())
+            .map_err(|_| BLErr::invalid_value())?;     Ok((img, profile))
+    }
+
+    fn render_timed(&self, opts: &RenderOptions,
+        mut times: Option<&mut Vec<(usize, Duration)>>) -> Result<BLImage, BLErr> {
+        let (width, height, trfm) = opts.layout(self.header.width, self.header.height);
 
         #[allow(non_local_definitions)] impl From<&Rect> for BLRect {   // BLBox
             //fn from(rect: &Rect) -> Self { unsafe { std::mem::transmute(rect) } }
@@ -24,147 +50,84 @@ impl<R: io::Read, W: io::Write> Render for TinyVG<R, W> {
             }
         }
 
-        // XXX: rendering up-scale and then scale down for anti-aliasing?
-        let (mut ctx, mut path) = (
-            BLContext::new(width, height, BLFormat::BL_FORMAT_PRGB32)?, BLPath::new());
+        // XXX: Blend2D always anti-aliases, `opts.anti_alias` just turns off supersampling
+        let (n, format) = (opts.samples(), BLFormat::BL_FORMAT_PRGB32);
+        let trfm = trfm.post_scale(n as _, n as _);
+        let threads = match opts.threads { 1 => 0, 0 => std::thread::available_parallelism()
+            .map_or(0, |n| n.get() as _), threads => threads as _ };   // same output anyway
+        let mut ctx = BLContext::with_threads(BLImage::new(width * n, height * n,
+            format)?, threads)?;
         ctx.set_stroke_join(BLStrokeJoin::BL_STROKE_JOIN_ROUND);
         ctx.set_stroke_caps(BLStrokeCap::BL_STROKE_CAP_ROUND);
         ctx.set_stroke_miter_limit(4.0);
-        ctx.scale((scale as _, scale as _));
+        ctx.apply_transform(&BLMatrix2D::new([trfm.sx as _, trfm.ky as _,
+            trfm.kx as _, trfm.sy as _, trfm.tx as _, trfm.ty as _]));
+        if let Some(color) = opts.background { ctx.fill_all_rgba32(color.into())?; }
         // XXX: does path needs to be transformed before fill/stroke?
 
-        for cmd in &self.commands {
-            match cmd { Command::EndOfDocument => (),
-                Command::FillPolyg(FillCMD { fill, coll }) => {
-                    let mut iter = coll.iter();
-                    if let Some(&pt) = iter.next() { path.move_to(pt.into()); }
-                    for &pt in iter { path.line_to(pt.into()); }
-                    path.close();
-                    ctx.fill_geometry_ext(&path, convert_style(self, fill)?.as_ref())?;
-                }
-                Command::FillRects(FillCMD { fill, coll }) => {
-                    let style = convert_style(self, fill)?;
-                    for rect in coll { path.add_rect(&rect.into(), None); }
-                    ctx.fill_geometry_ext(&path, style.as_ref())?;  //path.reset();
-                }
-                Command::FillPath (FillCMD { fill, coll }) => {
-                    let style = convert_style(self, fill)?;
-                    for seg in coll { segment_to_path(seg, &mut path)?; }
-                    ctx.fill_geometry_ext(&path, style.as_ref())?;  //path.reset();
-                }
-                Command::DrawLines(DrawCMD { line, lwidth, coll }) => {
-                    for line in coll {
-                        path.move_to(line.start.into());
-                        path.line_to(line.end.into());
-                    }
-                    ctx.set_stroke_width(*lwidth as _);
-                    ctx.stroke_geometry_ext(&path, convert_style(self, line)?.as_ref())?;
-                }
-                Command::DrawLoop (DrawCMD { line, lwidth, coll },
-                    strip) => {     let mut iter = coll.iter();
-                    if let Some(&pt) = iter.next() { path.move_to(pt.into()); }
-                    for &pt in iter { path.line_to(pt.into()); }
-
-                    if !*strip { path.close(); }
-                    ctx.set_stroke_width(*lwidth as _);
-                    ctx.stroke_geometry_ext(&path, convert_style(self, line)?.as_ref())?;
-                }
-                Command::DrawPath (DrawCMD {
-                    line, lwidth, coll }) => {
-                    let style = convert_style(self, line)?;
-                    ctx.set_stroke_width(*lwidth as _);
-
-                    for seg in coll {
-                        stroke_segment_path(seg, &mut ctx, style.as_ref())?; }
-                }
-                Command::OutlinePolyg(fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let mut iter = coll.iter();
-                    if let Some(&pt) = iter.next() { path.move_to(pt.into()); }
-                    for &pt in iter { path.line_to(pt.into()); }
-                    path.close();
-
-                    ctx.set_stroke_width(*lwidth as _);
-                    ctx.  fill_geometry_ext(&path, convert_style(self, fill)?.as_ref())?;
-                    ctx.stroke_geometry_ext(&path, convert_style(self, line)?.as_ref())?;
-                }
-                Command::OutlineRects(fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let paint = convert_style(self, fill)?;
-                    let pline = convert_style(self, line)?;
-                    ctx.set_stroke_width(*lwidth as _);
-
-                    for rect in coll { path.add_rect(&rect.into(), None); }
-                    ctx.  fill_geometry_ext(&path, paint.as_ref())?;
-                    ctx.stroke_geometry_ext(&path, pline.as_ref())?; //path.reset();
-                }
-                Command::OutlinePath (fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let paint = convert_style(self, fill)?;
-                    let pline = convert_style(self, line)?;
-
-                    ctx.set_stroke_width(*lwidth as _);     let mut res = false;
-                    for seg in coll { res = segment_to_path(seg, &mut path)?; }
-                    ctx.fill_geometry_ext(&path, paint.as_ref())?;
-
-                    if res { for seg in coll {
-                        stroke_segment_path(seg, &mut ctx, pline.as_ref())?;
-                    } } else { ctx.stroke_geometry_ext(&path, pline.as_ref())?; }
-                }
-            }   path.reset();
-        }   ctx.end()
+        let mask = opts.visible(self, &trfm, width * n, height * n);
+        let mut canvas = B2DCanvas { ctx, path: BLPath::new(),
+            hairline: min_line_width(&trfm) * n as f32, srgb: opts.srgb_gradient };
+        for (index, cmd) in self.commands.iter().enumerate().zip(mask)
+            .filter_map(|(cmd, show)| show.then_some(cmd)) {     let tnow = Instant::now();
+            canvas.draw_command(self, cmd)?;
+            if let Some(times) = times.as_deref_mut() { times.push((index, tnow.elapsed())) }
+        }
+
+        let img = canvas.ctx.end()?;    if n < 2 { return Ok(img) }
+        let mut data = downsample(opts, img.pixels().ok_or(BLErr::invalid_value())?,
+            width * n, img.stride() as _, true);
+        let mut ctx = BLContext::new(width, height, format)?;
+        ctx.set_comp_op(BLCompOp::BL_COMP_OP_SRC_COPY);
+
+        // SAFETY: `data` outlives `src`, which is dropped right after blitting
+        let src = unsafe { BLImage::from_buffer(width, height, format,
+            &mut data, (width * 4) as _)? };
+        ctx.blit_image(BLPointI { x: 0, y: 0 }, &src,
+            &BLRectI { x: 0, y: 0, w: width as _, h: height as _ })?;   drop(src);  ctx.end()
     }
 }
 
-fn stroke_segment_path(seg: &Segment, ctx: &mut BLContext,
-    style: &dyn B2DStyle) -> Result<(), BLErr> {
-    let mut path = BLPath::new();
-    path.move_to(seg.start.into());
-
-    for cmd in &seg.cmds {
-        if let Some(width) = cmd.lwidth {
-            if 1 < path.get_size() {
-                let start = path.get_last_vertex()?;
-                ctx.stroke_geometry_ext(&path, style)?;
-                path.reset(); path.move_to(start);
-            }   ctx.set_stroke_width(width as _);
-        }   process_segcmd(&mut path, &cmd.instr)?;
-    }   ctx.stroke_geometry_ext(&path, style)
-}
+struct B2DCanvas { ctx: BLContext, path: BLPath, hairline: f32, srgb: bool }
 
-fn segment_to_path(seg: &Segment, path: &mut BLPath) -> Result<bool, BLErr> {
-    path.move_to(seg.start.into());
-    let mut change_lw = false;
+impl Canvas for B2DCanvas {
+    type Paint = Box<dyn B2DStyle>;     type Error = BLErr;
 
-    for cmd in &seg.cmds {
-        if cmd.lwidth.is_some() { change_lw = true }
-        process_segcmd(path, &cmd.instr)?;
-    }   Ok(change_lw)
-}
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        convert_style(img, style, self.srgb)
+    }
+
+    fn move_to (&mut self, pt: Point) { self.path.move_to(pt.into()) }
+    fn line_to (&mut self, pt: Point) { self.path.line_to(pt.into()) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) {
+        self.path.quad_to(ctrl.into(), end.into())
+    }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.path.cubic_to(ctrl.0.into(), ctrl.1.into(), end.into())
+    }
+    fn arc_to(&mut self, _: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        self.path.elliptic_arc_to((radii.0 as _, radii.1 as _),
+            rotation as _, large, sweep, end.into())
+    }
+    fn close(&mut self) { self.path.close() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        self.path.add_rect(&rect.into(), None);     Ok(())
+    }
 
-fn process_segcmd(path: &mut BLPath, cmd: &SegInstr) -> Result<(), BLErr> {
-    match cmd {
-        SegInstr::ClosePath => path.close(),
-        SegInstr::Line  { end } => path.line_to((*end).into()),
-        SegInstr::HLine { x } => path.line_to(
-            (*x as _, path.get_last_vertex()?.y).into()),
-        SegInstr::VLine { y } => path.line_to(
-            (path.get_last_vertex()?.x, *y as _).into()),
-        SegInstr::CubicBezier { ctrl, end } =>
-            path.cubic_to(ctrl.0.into(), ctrl.1.into(), (*end).into()),
-        SegInstr::ArcCircle  { large, sweep, radius, end } =>
-            return path.elliptic_arc_to((*radius as _, *radius as _),
-                0.0, *large, *sweep, (*end).into()),
-        SegInstr::ArcEllipse { large, sweep, radii,
-            rotation, end } => return path.elliptic_arc_to((radii.0 as _, radii.1 as _),
-                *rotation as _, *large, *sweep, (*end).into()),
-        SegInstr::QuadBezier { ctrl, end } =>
-            path.quad_to((*ctrl).into(), (*end).into()),
-    }   Ok(())
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.ctx.fill_geometry_ext(&self.path, paint.as_ref())
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.ctx.set_stroke_width(width.max(self.hairline) as _);
+        self.ctx.stroke_geometry_ext(&self.path, paint.as_ref())
+    }
+    fn clear (&mut self) { self.path.reset() }
 }
 
 fn convert_style<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
-    style: &Style) -> Result<Box<dyn B2DStyle>, BLErr> {
+    style: &Style, srgb: bool) -> Result<Box<dyn B2DStyle>, BLErr> {
     #[allow(non_local_definitions)] impl From<RGBA8888> for BLRgba32 {
         fn from(color: RGBA8888) -> Self { Self { value: // convert to 0xAARRGGBB
             (color.a as u32) << 24 | (color.r as u32) << 16 |
@@ -179,16 +142,16 @@ fn convert_style<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
         Style::LinearGradient { points, cindex } => {
             let mut linear = BLGradient::new(
                 &BLLinearGradientValues::new(points.0.into(), points.1.into()))?;
-            linear.add_stop(0.0, img.lookup_color(cindex.0).into())?;
-            linear.add_stop(1.0, img.lookup_color(cindex.1).into())?;
+            for (pos, color) in gradient_stops(img.lookup_color(cindex.0),
+                img.lookup_color(cindex.1), srgb) { linear.add_stop(pos as _, color.into())?; }
             Ok(Box::new(linear)) //linear.scale(scale, scale)?;
         }
         Style::RadialGradient { points, cindex } => {
             let radius = (points.1.x - points.0.x).hypot(points.1.y - points.0.y);
             let mut radial = BLGradient::new(&BLRadialGradientValues::new(
                 points.0.into(), points.1.into(), (0., radius as _)))?;
-            radial.add_stop(0.0, img.lookup_color(cindex.0).into())?;
-            radial.add_stop(1.0, img.lookup_color(cindex.1).into())?;
+            for (pos, color) in gradient_stops(img.lookup_color(cindex.0),
+                img.lookup_color(cindex.1), srgb) { radial.add_stop(pos as _, color.into())?; }
             Ok(Box::new(radial)) //radial.scale(scale, scale)?;
         }
     }
@@ -1,14 +1,40 @@
 
 use crate::tinyvg::*;
-use crate::gpac_evg::*;
-use std::io;
+use crate::{gpac_evg::*, canvas::Canvas, render::{RenderOptions, Profile,
+    downsample, gradient_stops, min_line_width}};
+use std::{io, time::{Duration, Instant}};
 
-pub trait Render { fn render(&self, scale: f32) -> Result<Pixmap, EvgError>; }
+pub trait Render {
+    fn render(&self, scale: f32) -> Result<Pixmap, EvgError> {
+        self.render_with(&RenderOptions { scale, ..Default::default() })
+    }
+    /// Render with only the `visible` named layers switched on, or all if `None`.
+    fn render_layers(&self, scale: f32, visible: Option<&[&str]>) -> Result<Pixmap, EvgError> {
+        self.render_with(&RenderOptions { scale, layers: visible.map(|names|
+            names.iter().map(|name| name.to_string()).collect()), ..Default::default() })
+    }
+    fn render_with(&self, opts: &RenderOptions) -> Result<Pixmap, EvgError> {
+        self.render_timed(opts, None)
+    }
+    /// Render, recording the drawing time of each drawn command into `times` if any.
+    fn render_timed(&self, opts: &RenderOptions,
+        times: Option<&mut Vec<(usize, Duration)>>) -> Result<Pixmap, EvgError>;
+    /// Render single-threaded and profile each command, see `render::Profile`.
+    fn profile(&self, opts: &RenderOptions) -> Result<(Pixmap, Profile), EvgError>;
+}
 
 impl<R: io::Read, W: io::Write> Render for TinyVG<R, W> {
-    fn render(&self, scale: f32) -> Result<Pixmap, EvgError> {
-        let width  = (self.header.width  as f32 * scale).ceil() as _;
-        let height = (self.header.height as f32 * scale).ceil() as _;
+    fn profile(&self, opts: &RenderOptions) -> Result<(Pixmap, Profile), EvgError> {
+        let (opts, mut times) = (RenderOptions { threads: 1, ..opts.clone() }, vec![]);
+        let tnow = Instant::now();
+        let img = self.render_timed(&opts, Some(&mut times))?;
+        let profile = Profile::new(self, &opts, "GPAC/EVG", &times, tnow.elapsed())
+            .map_err(|_| EvgError::bad_parameter())?;     Ok((img, profile))
+    }
+
+    fn render_timed(&self, opts: &RenderOptions,
+        mut times: Option<&mut Vec<(usize, Duration)>>) -> Result<Pixmap, EvgError> {
+        let (width, height, trfm) = opts.layout(self.header.width, self.header.height);
 
         #[allow(non_local_definitions)] impl From<&Rect> for GF_Rect {
             fn from(rect: &Rect) -> Self {  // XXX: screen to world coordinates
@@ -17,145 +43,68 @@ impl<R: io::Read, W: io::Write> Render for TinyVG<R, W> {
             }
         }
 
-        // XXX: rendering up-scale and then scale down for anti-aliasing?
-        let mut surf = Surface::new(width, height, GF_PixelFormat::GF_PIXEL_RGBA)?;
-        let (mut path, mut pens) = (VGPath::new()?, PenSettings::default());
-
-        let trfm = GF_Matrix2D {
-            m: [scale.into(), 0.into(), 0.into(), 0.into(), scale.into(), 0.into()] };
-        surf.set_matrix(Some(&trfm));
-
-        for cmd in &self.commands {
-            match cmd { Command::EndOfDocument => (),
-                Command::FillPolyg(FillCMD { fill, coll }) => {
-                    let mut iter = coll.iter();
-                    if let Some(pt) = iter.next() { path.move_to((*pt).into()) }
-                    iter.for_each(|pt| path.line_to((*pt).into()));  path.close();
-                    surf.fill_path(&path, &style_to_stencil(self, fill)?)?;
-                }
-                Command::FillRects(FillCMD { fill, coll }) => {
-                    let sten = style_to_stencil(self, fill)?;
-                    coll.iter().for_each(|rect| path.add_rect(rect.into()));
-                    surf.fill_path(&path, &sten)?;    //path.reset();
-                }
-                Command::FillPath (FillCMD { fill, coll }) => {
-                    let sten = style_to_stencil(self, fill)?;
-                    for seg in coll { let _ = segment_to_path(seg, &mut path)?; }
-                    surf.fill_path(&path, &sten)?;    //path.reset();
-                }
-                Command::DrawLines(DrawCMD { line, lwidth, coll }) => {
-                    coll.iter().for_each(|line| {
-                        path.move_to(line.start.into()); path.line_to(line.  end.into());
-                    }); pens.set_width((*lwidth).into());
-                    surf.stroke_path(&path, &style_to_stencil(self, line)?, &pens)?;
-                }
-                Command::DrawLoop (DrawCMD { line, lwidth, coll },
-                    strip) => {     let mut iter = coll.iter();
-                    if let Some(pt) = iter.next() { path.move_to((*pt).into()) }
-                    iter.for_each(|pt| path.line_to((*pt).into()));
-
-                    if !*strip { path.close(); }    pens.set_width((*lwidth).into());
-                    surf.stroke_path(&path, &style_to_stencil(self, line)?, &pens)?;
-                }
-                Command::DrawPath (DrawCMD {
-                    line, lwidth, coll }) => {
-                    let sten = style_to_stencil(self, line)?;
-                    pens.set_width((*lwidth).into());
-
-                    for seg in coll {
-                        stroke_segment_path(seg, &mut surf, &sten, &mut pens)?; }
-                }
-                Command::OutlinePolyg(fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    pens.set_width((*lwidth).into());
-
-                    let mut iter = coll.iter();
-                    if let Some(pt) = iter.next() { path.move_to((*pt).into()) }
-                    iter.for_each(|pt| path.line_to((*pt).into()));  path.close();
-
-                    surf.fill_path(&path, &style_to_stencil(self, fill)?)?;
-                    surf.stroke_path(&path, &style_to_stencil(self, line)?, &pens)?;
-                }
-                Command::OutlineRects(fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    pens.set_width((*lwidth).into());
-                    let paint = style_to_stencil(self, fill)?;
-                    let pline = style_to_stencil(self, line)?;
-
-                    coll.iter().for_each(|rect| path.add_rect(rect.into()));
-                    surf.fill_path(&path, &paint)?;
-                    surf.stroke_path(&path, &pline, &pens)?;     //path.reset();
-                }
-                Command::OutlinePath (fill, DrawCMD {
-                    line, lwidth, coll }) => {
-                    let paint = style_to_stencil(self, fill)?;
-                    let pline = style_to_stencil(self, line)?;
-
-                    pens.set_width((*lwidth).into());  let mut res = false;
-                    for seg in coll { res = segment_to_path(seg, &mut path)?; }
-                    surf.fill_path(&path, &paint)?;
-
-                    if res { for seg in coll {
-                        stroke_segment_path(seg, &mut surf, &pline, &mut pens)?;
-                    } } else { surf.stroke_path(&path, &pline, &pens)?; }  //path.reset();
-                }
-            }   path.reset();
+        // XXX: GPAC/EVG rendering is single-threaded, `opts.threads` is ignored
+        let (n, format) = (opts.samples(), GF_PixelFormat::GF_PIXEL_RGBA);
+        let mut surf = Surface::new(width * n, height * n, format)?;
+        if !opts.anti_alias { surf.set_raster_level(GF_RasterQuality::GF_RASTER_HIGH_SPEED) }
+
+        let trfm = trfm.post_scale(n as _, n as _);     surf.set_matrix(Some(&trfm.into()));
+        if let Some(color) = opts.background { surf.clear(None, color.into())?; }
+
+        let mask = opts.visible(self, &trfm, width * n, height * n);
+        let mut canvas = EvgCanvas { surf, path: VGPath::new()?, pens: PenSettings::default(),
+            hairline: min_line_width(&trfm) * n as f32, srgb: opts.srgb_gradient };
+        for (index, cmd) in self.commands.iter().enumerate().zip(mask)
+            .filter_map(|(cmd, show)| show.then_some(cmd)) {     let tnow = Instant::now();
+            canvas.draw_command(self, cmd)?;
+            if let Some(times) = times.as_deref_mut() { times.push((index, tnow.elapsed())) }
         }
-        Ok(surf.end())
+
+        let pixm = canvas.surf.end();  if n < 2 { return Ok(pixm) }    // straight alpha
+        Pixmap::from_vec(downsample(opts, pixm.data(), width * n, (width * n * 4) as _, false),
+            width, height, format)
     }
 }
 
-fn stroke_segment_path(seg: &Segment, surf: &mut Surface,
-    sten: &Stencil, pens: &mut PenSettings) -> Result<(), EvgError> {
-    let mut path = VGPath::new()?;
-    path.move_to(seg.start.into());
-
-    for cmd in &seg.cmds {
-        if let Some(width) = cmd.lwidth {
-            if 1 < path.len() {
-                let start = path.last_point().unwrap();
-                surf.stroke_path(&path, sten, pens)?;
-                path.reset(); path.move_to(start);
-            }   pens.set_width(width.into());
-        }   process_segcmd(&mut path, &cmd.instr)?;
-    }   surf.stroke_path(&path, sten, pens)
-}
+struct EvgCanvas { surf: Surface, path: VGPath, pens: PenSettings, hairline: f32, srgb: bool }
 
-fn segment_to_path(seg: &Segment, path: &mut VGPath) -> Result<bool, EvgError> {
-    path.move_to(seg.start.into());
-    let mut change_lw = false;
+impl Canvas for EvgCanvas {
+    type Paint = Stencil;   type Error = EvgError;
 
-    for cmd in &seg.cmds {
-        if cmd.lwidth.is_some() { change_lw = true }
-        process_segcmd(path, &cmd.instr)?;
-    }   Ok(change_lw)
-}
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        style_to_stencil(img, style, self.srgb)
+    }
 
-fn process_segcmd(path: &mut VGPath, cmd: &SegInstr) -> Result<(), EvgError> {
-    match cmd {     SegInstr::ClosePath => path.close(),
-        SegInstr::Line  { end } => path.line_to((*end).into()),
-        SegInstr::HLine { x }     =>
-            path.line_to(((*x).into(), path.last_point().unwrap().y).into()),
-        SegInstr::VLine { y }     =>
-            path.line_to((path.last_point().unwrap().x, (*y).into()).into()),
-
-        SegInstr::CubicBezier { ctrl, end } =>
-            path.cubic_to(ctrl.0.into(), ctrl.1.into(), (*end).into()),
-        SegInstr::ArcCircle  { large, sweep, radius, end } =>
-            return path.svg_arc_to(
-                (*radius, *radius).into(), 0.into(), *large, *sweep, (*end).into()),
-
-        SegInstr::ArcEllipse { large, sweep, radii,
-            rotation, end } => return path.svg_arc_to((*radii).into(),
-                (*rotation).into(), *large, *sweep, (*end).into()),
-
-        SegInstr::QuadBezier { ctrl, end } =>
-            path.quad_to((*ctrl).into(), (*end).into()),
-    }   Ok(())
+    fn move_to (&mut self, pt: Point) { self.path.move_to(pt.into()) }
+    fn line_to (&mut self, pt: Point) { self.path.line_to(pt.into()) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) {
+        self.path.quad_to(ctrl.into(), end.into())
+    }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.path.cubic_to(ctrl.0.into(), ctrl.1.into(), end.into())
+    }
+    fn arc_to(&mut self, _: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        self.path.svg_arc_to(radii.into(), rotation.into(), large, sweep, end.into())
+    }
+    fn close(&mut self) { self.path.close() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        self.path.add_rect(rect.into());    Ok(())
+    }
+
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.surf.fill_path(&self.path, paint)
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.pens.set_width(width.max(self.hairline).into());
+        self.surf.stroke_path(&self.path, paint, &self.pens)
+    }
+    fn clear (&mut self) { self.path.reset() }
 }
 
 fn style_to_stencil<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
-    style: &Style) -> Result<Stencil, EvgError> {
+    style: &Style, srgb: bool) -> Result<Stencil, EvgError> {
     #[allow(non_local_definitions)] impl From<RGBA8888> for GF_Color {
         fn from(color: RGBA8888) -> Self { // convert to 0xAARRGGBB
             (color.a as u32) << 24 | (color.r as u32) << 16 |
@@ -170,15 +119,19 @@ fn style_to_stencil<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
         }
         Style::LinearGradient { points, cindex } => {
             let mut sten = Stencil::new(GF_STENCIL_LINEAR_GRADIENT)?;
-            sten.push_interpolation(0.into(), img.lookup_color(cindex.0).into());
-            sten.push_interpolation(1.into(), img.lookup_color(cindex.1).into());
+            for (pos, color) in gradient_slots(img.lookup_color(cindex.0),
+                img.lookup_color(cindex.1), srgb) {
+                sten.push_interpolation(pos.into(), color.into());
+            }
             sten.set_linear(points.0.into(), points.1.into());
             Ok(sten)    //sten.set_matrix(trfm);
         }
         Style::RadialGradient { points, cindex } => {
             let mut sten = Stencil::new(GF_STENCIL_RADIAL_GRADIENT)?;
-            sten.push_interpolation(0.into(), img.lookup_color(cindex.0).into());
-            sten.push_interpolation(1.into(), img.lookup_color(cindex.1).into());
+            for (pos, color) in gradient_slots(img.lookup_color(cindex.0),
+                img.lookup_color(cindex.1), srgb) {
+                sten.push_interpolation(pos.into(), color.into());
+            }
             let radius = (points.1.x - points.0.x).hypot(points.1.y - points.0.y);
             sten.set_radial(points.0.into(), points.1.into(), (radius, radius).into());
             Ok(sten)    //sten.set_matrix(trfm);
@@ -186,12 +139,19 @@ fn style_to_stencil<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
     }
 }
 
+/// GPAC stencils hold at most `EVGGRADIENTSLOTS` (12) interpolation stops.
+fn gradient_slots(c0: RGBA8888, c1: RGBA8888, srgb: bool) -> Vec<(f32, RGBA8888)> {
+    const SLOTS: usize = 12;
+    let stops = gradient_stops(c0, c1, srgb);   if stops.len() <= SLOTS { return stops }
+    (0..SLOTS).map(|i| stops[i * (stops.len() - 1) / (SLOTS - 1)]).collect()
+}
+
 impl From<Point> for GF_Point2D { fn from(pt: Point) -> Self { (pt.x, pt.y).into() } }
 
-/* impl From<tiny_skia::Transform> for GF_Matrix2D {
+impl From<tiny_skia::Transform> for GF_Matrix2D {
     fn from(mv: tiny_skia::Transform) -> Self {
         Self { m: [mv.sx.into(), mv.kx.into(), mv.tx.into(),
-                   mv.sy.into(), mv.ky.into(), mv.ty.into()] }
-        // sx = m[0], kx = m[1], tx = m[2], sy = m[3], ky = m[4], ty = m[5]
+                   mv.ky.into(), mv.sy.into(), mv.ty.into()] }
+        // sx = m[0], kx = m[1], tx = m[2], ky = m[3], sy = m[4], ty = m[5]
     }
-} */
+}
@@ -0,0 +1,209 @@
+
+//! TinyVG rendering by the pure-Rust `grays` rasterizer, painting its spans with solid
+//! colors and gradients, strokes are outlined by kurbo.
+
+use crate::tinyvg::*;
+use crate::{grays::*, canvas::{Canvas, svg_arc}, render::{RenderOptions, Profile,
+    downsample, gradient_stops, min_line_width}};
+use std::{io, time::{Duration, Instant}};
+use tiny_skia as skia;
+
+pub trait Render {
+    fn render(&self, scale: f32) -> Result<skia::Pixmap, &'static str> {
+        self.render_with(&RenderOptions { scale, ..Default::default() })
+    }
+    /// Render with only the `visible` named layers switched on, or all if `None`.
+    fn render_layers(&self, scale: f32,
+        visible: Option<&[&str]>) -> Result<skia::Pixmap, &'static str> {
+        self.render_with(&RenderOptions { scale, layers: visible.map(|names|
+            names.iter().map(|name| name.to_string()).collect()), ..Default::default() })
+    }
+    fn render_with(&self, opts: &RenderOptions) -> Result<skia::Pixmap, &'static str> {
+        self.render_timed(opts, None)
+    }
+    /// Render, recording the drawing time of each drawn command into `times` if any.
+    fn render_timed(&self, opts: &RenderOptions,
+        times: Option<&mut Vec<(usize, Duration)>>) -> Result<skia::Pixmap, &'static str>;
+    /// Render and profile each command, see `render::Profile`.
+    fn profile(&self, opts: &RenderOptions) -> Result<(skia::Pixmap, Profile), &'static str>;
+}
+
+impl<R: io::Read, W: io::Write> Render for TinyVG<R, W> {
+    fn profile(&self, opts: &RenderOptions) -> Result<(skia::Pixmap, Profile), &'static str> {
+        let (tnow, mut times) = (Instant::now(), vec![]);
+        let img = self.render_timed(opts, Some(&mut times))?;
+        let profile = Profile::new(self, opts, "grays", &times, tnow.elapsed())?;
+        Ok((img, profile))
+    }
+
+    fn render_timed(&self, opts: &RenderOptions,
+        mut times: Option<&mut Vec<(usize, Duration)>>) -> Result<skia::Pixmap, &'static str> {
+        let (width, height, trfm) = opts.layout(self.header.width, self.header.height);
+
+        // XXX: the grays rasterizer is single-threaded, `opts.threads` is ignored
+        let (n, err_msg) = (opts.samples(), "Fail to create pixmap");
+        let mut pixmap = skia::Pixmap::new(width * n, height * n).ok_or(err_msg)?;
+        if let Some(color) = opts.background { pixmap.fill(color.into()) }
+
+        let strfm = trfm.post_scale(n as _, n as _);
+        let mask = opts.visible(self, &strfm, width * n, height * n);
+        let mut canvas = GraysCanvas { target: Target {
+                raster: Rasterizer::new(width * n, height * n), pixmap,
+                trfm: kurbo::Affine::new([strfm.sx, strfm.ky, strfm.kx,
+                    strfm.sy, strfm.tx, strfm.ty].map(|v| v as f64)),
+                inverse: strfm.invert().ok_or("Non-invertible transform")?,
+                anti_alias: opts.anti_alias },
+            path: kurbo::BezPath::new(), hairline: min_line_width(&trfm),
+            tolerance: min_line_width(&strfm) as f64 / 4.0, srgb: opts.srgb_gradient };
+        for (index, cmd) in self.commands.iter().enumerate().zip(mask)
+            .filter_map(|(cmd, show)| show.then_some(cmd)) {     let tnow = Instant::now();
+            canvas.draw_command(self, cmd)?;
+            if let Some(times) = times.as_deref_mut() { times.push((index, tnow.elapsed())) }
+        }
+
+        let pixmap = canvas.target.pixmap;  if n < 2 { return Ok(pixmap) }
+        skia::Pixmap::from_vec(downsample(opts, pixmap.data(), width * n,
+            (width * n * 4) as _, true), skia::IntSize::from_wh(width, height)
+            .ok_or(err_msg)?).ok_or(err_msg)
+    }
+}
+
+/// Paint of the spans, with colors premultiplied by alpha and gradients looked up
+/// in 256 entries by the position `t` of a pixel in image coordinates.
+pub enum Paint {   Solid([u8; 4]),
+    Linear { start: Point, dir: (f32, f32), lut: Vec<[u8; 4]> },
+    Radial { center: Point, radius: f32,     lut: Vec<[u8; 4]> },
+}
+
+impl Paint {
+    pub fn new<R: io::Read, W: io::Write>(img: &TinyVG<R, W>, style: &Style,
+        srgb: bool) -> Self {
+        let lut = |cindex: &(u32, u32)| gradient_lut(&gradient_stops(
+            img.lookup_color(cindex.0), img.lookup_color(cindex.1), srgb));
+
+        match style {
+            Style::FlatColor(idx) => Self::Solid(premultiply(img.lookup_color(*idx))),
+            Style::LinearGradient { points, cindex } => {
+                let (dx, dy) = (points.1.x - points.0.x, points.1.y - points.0.y);
+                let len2 = dx * dx + dy * dy;
+                let dir = if 0.0 < len2 { (dx / len2, dy / len2) } else { (0.0, 0.0) };
+                Self::Linear { start: points.0, dir, lut: lut(cindex) }
+            }
+            Style::RadialGradient { points, cindex } => Self::Radial { center: points.0,
+                radius: (points.1.x - points.0.x).hypot(points.1.y - points.0.y),
+                lut: lut(cindex) },
+        }
+    }
+
+    /// Color at `pt` in image coordinates.
+    pub fn shade(&self, pt: Point) -> [u8; 4] {
+        let index = |t: f32| (t.clamp(0.0, 1.0) * 255.0 + 0.5) as usize;   // padded
+        match self {    Self::Solid(color) => *color,
+            Self::Linear { start, dir, lut } =>
+                lut[index((pt.x - start.x) * dir.0 + (pt.y - start.y) * dir.1)],
+            Self::Radial { center, radius, lut } => lut[index(if 0.0 < *radius {
+                (pt.x - center.x).hypot(pt.y - center.y) / radius } else { 1.0 })],
+        }
+    }
+}
+
+/// The rasterizer with the pixmap to paint, and the transforms of the image into it.
+struct Target { raster: Rasterizer, pixmap: skia::Pixmap,
+    trfm: kurbo::Affine, inverse: skia::Transform, anti_alias: bool }
+
+impl Target {
+    fn fill(&mut self, path: impl IntoIterator<Item = kurbo::PathEl>, paint: &Paint) {
+        let trfm = self.trfm;   self.raster.add_path(path.into_iter().map(|el| trfm * el));
+        let (width, inverse) = (self.pixmap.width() as usize, self.inverse);
+        let (data, anti_alias) = (self.pixmap.data_mut(), self.anti_alias);
+
+        self.raster.render(FillRule::NonZero, |y, spans| for span in spans {
+            let coverage = if anti_alias { span.coverage
+            } else if 128 <= span.coverage { 255 } else { continue };
+            let row = &mut data[(y as usize * width + span.x as usize) * 4..]
+                [..span.len as usize * 4];
+
+            for (x, pixel) in (span.x..).zip(row.chunks_exact_mut(4)) {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let color = paint.shade(Point {
+                    x: inverse.sx * px + inverse.kx * py + inverse.tx,
+                    y: inverse.ky * px + inverse.sy * py + inverse.ty });
+                blend(pixel, color, coverage);
+            }
+        });
+    }
+}
+
+struct GraysCanvas { target: Target, path: kurbo::BezPath,
+    hairline: f32, tolerance: f64, srgb: bool }
+
+impl Canvas for GraysCanvas {
+    type Paint = Paint;     type Error = &'static str;
+
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        Ok(Paint::new(img, style, self.srgb))
+    }
+
+    fn move_to (&mut self, pt: Point) { self.path.move_to(kpt(pt)) }
+    fn line_to (&mut self, pt: Point) { self.path.line_to(kpt(pt)) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) { self.path.quad_to(kpt(ctrl), kpt(end)) }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.path.curve_to(kpt(ctrl.0), kpt(ctrl.1), kpt(end))
+    }
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        match kurbo::Arc::from_svg_arc(&svg_arc(start, radii, rotation, large, sweep, end)) {
+            None => self.path.line_to(kpt(end)),
+            Some(arc) => arc.to_cubic_beziers(0.1, |p1, p2, p|
+                self.path.curve_to(p1, p2, p)),
+        }   Ok(())
+    }
+    fn close(&mut self) { self.path.close_path() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        self.path.move_to((rect.x as f64, rect.y as f64));
+        self.path.line_to(((rect.x + rect.w) as f64, rect.y as f64));
+        self.path.line_to(((rect.x + rect.w) as f64, (rect.y + rect.h) as f64));
+        self.path.line_to((rect.x as f64, (rect.y + rect.h) as f64));
+        self.path.close_path();     Ok(())
+    }
+
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.target.fill(&self.path, paint);    Ok(())
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        let style = kurbo::Stroke::new(width.max(self.hairline) as _)
+            .with_join(kurbo::Join::Round).with_caps(kurbo::Cap::Round).with_miter_limit(4.0);
+        self.target.fill(kurbo::stroke(&self.path, &style,
+            &kurbo::StrokeOpts::default(), self.tolerance), paint);     Ok(())
+    }
+    fn clear (&mut self) { self.path.truncate(0) }
+}
+
+fn kpt(pt: Point) -> kurbo::Point { kurbo::Point::new(pt.x as _, pt.y as _) }
+
+fn premultiply(color: RGBA8888) -> [u8; 4] {
+    let a = color.a;    [mul_div255(color.r, a), mul_div255(color.g, a), mul_div255(color.b, a), a]
+}
+
+/// Premultiplied colors of 256 evenly spaced positions between the (sorted) `stops`.
+fn gradient_lut(stops: &[(f32, RGBA8888)]) -> Vec<[u8; 4]> {
+    (0..256).map(|i| {  let t = i as f32 / 255.0;
+        let k = stops.iter().rposition(|(pos, _)| *pos <= t).unwrap_or(0);
+        let ((p0, c0), (p1, c1)) = (stops[k], stops[(k + 1).min(stops.len() - 1)]);
+        let f = if p0 < p1 { ((t - p0) / (p1 - p0)).clamp(0.0, 1.0) } else { 0.0 };
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f + 0.5) as u8;
+        premultiply(RGBA8888 { r: lerp(c0.r, c1.r), g: lerp(c0.g, c1.g),
+                               b: lerp(c0.b, c1.b), a: lerp(c0.a, c1.a) })
+    }).collect()
+}
+
+/// Source-over blending of the premultiplied `src` with `coverage` into `dst`.
+fn blend(dst: &mut [u8], src: [u8; 4], coverage: u8) {
+    let src = src.map(|c| mul_div255(c, coverage));     let inv = 255 - src[3];
+    dst.iter_mut().zip(src).for_each(|(d, s)| *d = s + mul_div255(*d, inv));
+}
+
+#[inline] fn mul_div255(a: u8, b: u8) -> u8 {
+    let v = a as u32 * b as u32 + 128;  ((v + (v >> 8)) >> 8) as _
+}
@@ -0,0 +1,259 @@
+
+//! SVG export of TinyVG: each command into `<path>`/`<rect>`/`<polygon>`/`<polyline>`
+//! elements, and gradients into `<defs>` in user space, e.g. to edit in design tools.
+
+use crate::{tinyvg::*, trailer::html_escape};
+use std::{cmp::Reverse, io};
+
+pub trait SvgExport {
+    /// Write a standalone SVG document of the size in the header.
+    fn save_svg(&self, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl<R: io::Read, W: io::Write> SvgExport for TinyVG<R, W> {
+    fn save_svg(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let (width, height) = (self.header.width, self.header.height);
+        writeln!(writer, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+            height=\"{height}\" viewBox=\"0 0 {width} {height}\">")?;
+
+        let mut svg = SvgWriter { img: self, defs: vec![], body: String::new(), indent: 1 };
+        let mut names = self.named_ranges();    // into `id` of elements or groups
+        names.sort_by_key(|nr| (nr.range.start, Reverse(nr.range.end), nr.depth));
+        let (mut names, mut ends) = (names.into_iter().peekable(), vec![]);
+
+        for (idx, cmd) in self.commands.iter().enumerate() {
+            while ends.last().is_some_and(|end| *end <= idx) {
+                ends.pop();     svg.indent -= 1;    svg.push("</g>");
+            }
+
+            let mut leaf = None::<String>;  // XXX: skip those not properly nested
+            while let Some(nr) = names.next_if(|nr| nr.range.start <= idx) {
+                if nr.range.start < idx || nr.range.is_empty() ||
+                    ends.last().is_some_and(|end| *end < nr.range.end) { continue }
+                if let Some(name) = leaf.take() {
+                    svg.push(&format!("<g id=\"{}\">", html_escape(&name)));
+                    ends.push(idx + 1);     svg.indent += 1;
+                }
+                if nr.range.len() == 1 { leaf = Some(nr.name); continue }
+                svg.push(&format!("<g id=\"{}\">", html_escape(&nr.name)));
+                ends.push(nr.range.end);    svg.indent += 1;
+            }
+
+            let start = svg.body.len();     svg.command(cmd);
+            let Some(name) = leaf else { continue };
+            let id = format!(" id=\"{}\"", html_escape(&name));
+            if svg.body[start..].lines().count() == 1 {   // onto the single element
+                let pos = svg.body[start..].find('<').map_or(start, |off| start + off);
+                let pos = svg.body[pos..].find(' ').map_or(pos, |off| pos + off);
+                svg.body.insert_str(pos, &id);
+            } else {
+                let elems = svg.body.split_off(start);  svg.push(&format!("<g{id}>"));
+                svg.indent += 1;    elems.lines().for_each(|elem| svg.push(elem.trim_start()));
+                svg.indent -= 1;    svg.push("</g>");
+            }
+        }
+        for _ in ends { svg.indent -= 1;    svg.push("</g>"); }
+
+        if !svg.defs.is_empty() {   writeln!(writer, "  <defs>")?;
+            for (idx, (kind, def)) in svg.defs.iter().enumerate() {
+                writeln!(writer, "    <{kind} id=\"grad{idx}\" \
+                    gradientUnits=\"userSpaceOnUse\" color-interpolation=\"linearRGB\" \
+                    {def}</{kind}>")?;
+            }   writeln!(writer, "  </defs>")?;
+        }   writeln!(writer, "{}</svg>", svg.body)
+    }
+}
+
+/// Elements are written into `body` at the group nesting level `indent`,
+/// gradients (deduplicated) into `defs`.
+struct SvgWriter<'a, R: io::Read, W: io::Write> {
+    img: &'a TinyVG<R, W>, defs: Vec<(&'static str, String)>, body: String, indent: usize,
+}
+
+impl<R: io::Read, W: io::Write> SvgWriter<'_, R, W> {
+    fn command(&mut self, cmd: &Command) {
+        match cmd {     Command::EndOfDocument => (),
+            Command::FillPolyg(FillCMD { fill, coll }) => {
+                let attrs = self.attrs(Some(fill), None);   self.polygon("polygon", coll, &attrs);
+            }
+            Command::FillRects(FillCMD { fill, coll }) => {
+                let attrs = self.attrs(Some(fill), None);
+                for rect in coll { self.rect(rect, &attrs) }
+            }
+            Command::FillPath (FillCMD { fill, coll }) => {
+                let attrs = self.attrs(Some(fill), None);   self.path(&path_data(coll), &attrs);
+            }
+            Command::DrawLines(DrawCMD { line, lwidth, coll }) => {
+                let attrs = self.attrs(None, Some((line, *lwidth)));
+                let data = coll.iter().map(|line| format!("M{} L{}",
+                    point(&line.start), point(&line.end))).collect::<Vec<_>>().join(" ");
+                self.path(&data, &attrs);
+            }
+            Command::DrawLoop (DrawCMD { line, lwidth, coll }, strip) => {
+                let attrs = self.attrs(None, Some((line, *lwidth)));
+                self.polygon(if *strip { "polyline" } else { "polygon" }, coll, &attrs);
+            }
+            Command::DrawPath (DrawCMD { line, lwidth, coll }) =>
+                self.stroke_segments(coll, line, *lwidth),
+
+            Command::OutlinePolyg(fill, DrawCMD { line, lwidth, coll }) => {
+                let attrs = self.attrs(Some(fill), Some((line, *lwidth)));
+                self.polygon("polygon", coll, &attrs);
+            }
+            Command::OutlineRects(fill, DrawCMD { line, lwidth, coll }) => {
+                if let [rect] = coll.as_slice() {
+                    let attrs = self.attrs(Some(fill), Some((line, *lwidth)));
+                    self.rect(rect, &attrs);    return
+                }   // all filled before stroked, as rendered
+
+                let attrs = self.attrs(Some(fill), None);
+                for rect in coll { self.rect(rect, &attrs) }
+                let attrs = self.attrs(None, Some((line, *lwidth)));
+                for rect in coll { self.rect(rect, &attrs) }
+            }
+            Command::OutlinePath (fill, DrawCMD { line, lwidth, coll }) => {
+                if coll.iter().flat_map(|seg| &seg.cmds).any(|cmd| cmd.lwidth.is_some()) {
+                    let attrs = self.attrs(Some(fill), None);
+                    self.path(&path_data(coll), &attrs);
+                    self.stroke_segments(coll, line, *lwidth);
+                } else {
+                    let attrs = self.attrs(Some(fill), Some((line, *lwidth)));
+                    self.path(&path_data(coll), &attrs);
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, elem: &str) {
+        self.body.push_str(&"  ".repeat(self.indent));  self.body.push_str(elem);
+        self.body.push('\n');
+    }
+    fn path(&mut self, data: &str, attrs: &str) {
+        self.push(&format!("<path d=\"{data}\"{attrs}/>"))
+    }
+    fn rect(&mut self, rect: &Rect, attrs: &str) {
+        self.push(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{attrs}/>",
+            rect.x, rect.y, rect.w, rect.h))
+    }
+    fn polygon(&mut self, kind: &str, coll: &[Point], attrs: &str) {
+        let points = coll.iter().map(|pt|
+            format!("{},{}", pt.x, pt.y)).collect::<Vec<_>>().join(" ");
+        self.push(&format!("<{kind} points=\"{points}\"{attrs}/>"))
+    }
+
+    /// Stroke the segments in paths split where the line width changes, starting with `lwidth`.
+    fn stroke_segments(&mut self, coll: &[Segment], line: &Style, mut lwidth: f32) {
+        let (mut data, mut drawn) = (String::new(), false);
+        for seg in coll {   let (mut last, mut split) = (seg.start, false);
+            data.push_str(&format!(" M{}", point(&seg.start)));
+
+            for cmd in &seg.cmds {
+                if let Some(width) = cmd.lwidth.filter(|width| *width != lwidth) {
+                    if drawn {  let attrs = self.attrs(None, Some((line, lwidth)));
+                        self.path(data.trim_start(), &attrs);
+                        data = format!(" M{}", point(&last));   (drawn, split) = (false, true);
+                    }   lwidth = width;
+                }
+
+                if split && matches!(cmd.instr, SegInstr::ClosePath) {  // not a new subpath
+                    data.push_str(&format!(" L{}", point(&seg.start)));
+                    (last, drawn) = (seg.start, true);  continue
+                }   segment_data(&mut data, &cmd.instr, &mut last, seg.start);
+                drawn |= !matches!(cmd.instr, SegInstr::ClosePath);
+            }
+        }
+
+        if drawn {  let attrs = self.attrs(None, Some((line, lwidth)));
+            self.path(data.trim_start(), &attrs);
+        }
+    }
+
+    /// Presentation attributes of the `fill` and the `line` style with its width,
+    /// TinyVG lines have round caps and joins.
+    fn attrs(&mut self, fill: Option<&Style>, line: Option<(&Style, f32)>) -> String {
+        let mut attrs = String::new();
+        match fill {    None => attrs.push_str(" fill=\"none\""),
+            Some(style) => {    let (paint, opacity) = self.paint(style);
+                attrs.push_str(&format!(" fill=\"{paint}\""));
+                if let Some(opacity) = opacity {
+                    attrs.push_str(&format!(" fill-opacity=\"{opacity:.3}\"")) }
+            }
+        }
+
+        if let Some((style, lwidth)) = line {   let (paint, opacity) = self.paint(style);
+            attrs.push_str(&format!(" stroke=\"{paint}\""));
+            if let Some(opacity) = opacity {
+                attrs.push_str(&format!(" stroke-opacity=\"{opacity:.3}\"")) }
+
+            // XXX: SVG doesn't draw 0 wide strokes, TinyVG draws a display pixel wide
+            if 0.0 < lwidth { attrs.push_str(&format!(" stroke-width=\"{lwidth}\""));
+            } else { attrs.push_str(" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"") }
+            attrs.push_str(" stroke-linecap=\"round\" stroke-linejoin=\"round\"");
+        }   attrs
+    }
+
+    /// The paint of `style` as an attribute value, with the opacity if not opaque.
+    fn paint(&mut self, style: &Style) -> (String, Option<f32>) {
+        let (kind, def, cindex) = match style {
+            Style::FlatColor(idx) => {  let color = self.img.lookup_color(*idx);
+                return (hex_color(color), (color.a < 255).then_some(color.a as f32 / 255.0))
+            }
+            // XXX: color-interpolation isn't supported by resvg yet
+            Style::LinearGradient { points: (p0, p1), cindex } => ("linearGradient",
+                format!("x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\">", p0.x, p0.y, p1.x, p1.y),
+                cindex),
+            Style::RadialGradient { points: (p0, p1), cindex } => ("radialGradient",
+                format!("cx=\"{}\" cy=\"{}\" r=\"{}\">", p0.x, p0.y,
+                    (p1.x - p0.x).hypot(p1.y - p0.y)), cindex),
+        };
+
+        let stop = |offset: u8, color: RGBA8888| {
+            let opacity = if color.a < 255 {
+                format!(" stop-opacity=\"{:.3}\"", color.a as f32 / 255.0) } else { "".into() };
+            format!("<stop offset=\"{offset}\" stop-color=\"{}\"{opacity}/>", hex_color(color))
+        };
+        let def = def + &stop(0, self.img.lookup_color(cindex.0)) +
+                        &stop(1, self.img.lookup_color(cindex.1));
+
+        let idx = self.defs.iter().position(|(k, d)| *k == kind && *d == def)
+            .unwrap_or_else(|| { self.defs.push((kind, def));  self.defs.len() - 1 });
+        (format!("url(#grad{idx})"), None)
+    }
+}
+
+/// SVG path data of the segments, each starts a subpath.
+fn path_data(coll: &[Segment]) -> String {
+    let mut data = String::new();
+    for seg in coll {   let mut last = seg.start;
+        data.push_str(&format!(" M{}", point(&seg.start)));
+        for cmd in &seg.cmds { segment_data(&mut data, &cmd.instr, &mut last, seg.start) }
+    }   data.trim_start().to_owned()
+}
+
+/// Append an instruction to the path `data`, following the current point `last`,
+/// which returns to `start` on closing.
+fn segment_data(data: &mut String, instr: &SegInstr, last: &mut Point, start: Point) {
+    let (text, end) = match instr {
+        SegInstr::ClosePath => (" Z".to_owned(), start),
+        SegInstr::Line  { end } => (format!(" L{}", point(end)), *end),
+        SegInstr::HLine { x } => (format!(" H{x}"), Point { x: *x, y: last.y }),
+        SegInstr::VLine { y } => (format!(" V{y}"), Point { x: last.x, y: *y }),
+
+        SegInstr::CubicBezier { ctrl, end } => (format!(" C{} {} {}",
+            point(&ctrl.0), point(&ctrl.1), point(end)), *end),
+        SegInstr::ArcCircle  { large, sweep, radius, end } =>
+            (format!(" A{radius} {radius} 0 {} {} {}",
+                *large as u8, !*sweep as u8, point(end)), *end),
+        SegInstr::ArcEllipse { large, sweep, radii, rotation, end } =>
+            (format!(" A{} {} {rotation} {} {} {}", radii.0, radii.1,
+                *large as u8, !*sweep as u8, point(end)), *end),
+        SegInstr::QuadBezier { ctrl, end } =>
+            (format!(" Q{} {}", point(ctrl), point(end)), *end),
+    };  data.push_str(&text);   *last = end;
+}
+
+#[inline] fn point(pt: &Point) -> String { format!("{} {}", pt.x, pt.y) }
+
+#[inline] fn hex_color(color: RGBA8888) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
@@ -20,7 +20,7 @@ impl Display for ErrorKind {
 }
 
 type Result<T> = std::result::Result<T, TVGError>;
-#[derive(Debug)] pub struct TVGError { kind: ErrorKind, msg: &'static str, }
+#[derive(Debug)] pub struct TVGError { pub(crate) kind: ErrorKind, pub(crate) msg: &'static str, }
 
 impl Display for TVGError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -106,6 +106,18 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
         } else { self.color_table.push(color);  self.color_table.len() as u32 - 1 }
     }
 
+    /// Select the _Unit_ encoding, keep the reader/writer in sync with the header.
+    pub(crate) fn set_coord_range(&mut self, range: CoordinateRange) {
+        match range {
+            CoordinateRange::Default  => { self.write_range = Self::write_default;
+                                           self. read_range = Self:: read_default;  }
+            CoordinateRange::Reduced  => { self.write_range = Self::write_reduced;
+                                           self. read_range = Self:: read_reduced;  }
+            CoordinateRange::Enhanced => { self.write_range = Self::write_enhanced;
+                                           self. read_range = Self:: read_enhanced; }
+        }   self.header.coord_range = range;
+    }
+
     pub fn load_data(reader: &mut R) -> Result<Self> {
         let mut tvgd = Self::new();
 
@@ -121,17 +133,12 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
         let val = reader.read_u8()?;   tvgd.header.scale = val & 0x0F;
         // TODO: scale rendering by change tvgd.header.scale?
 
-        tvgd.header.coord_range = match val >> 6 {
-            0 => { //tvgd.write_range = Self::write_default;
-                   //tvgd. read_range = Self:: read_default;
-                                                            CoordinateRange::Default  }
-            1 => { tvgd.write_range = Self::write_reduced;
-                   tvgd. read_range = Self:: read_reduced;  CoordinateRange::Reduced  }
-            2 => { tvgd.write_range = Self::write_enhanced;
-                   tvgd. read_range = Self:: read_enhanced; CoordinateRange::Enhanced }
+        tvgd.set_coord_range(match val >> 6 {
+            0 => CoordinateRange::Default,  1 => CoordinateRange::Reduced,
+            2 => CoordinateRange::Enhanced,
             x => return Err(TVGError { kind: ErrorKind::InvalidData(x),
                 msg: "unsupported color encoding" })
-        };  assert!(tvgd.commands.is_empty() && tvgd.color_table.is_empty());
+        }); assert!(tvgd.commands.is_empty() && tvgd.color_table.is_empty());
 
         let (width, height) = ((tvgd.read_range)(reader)?, (tvgd.read_range)(reader)?);
         // The spec uses zero to encode the coordinate range's maximum dimension.
@@ -364,7 +371,90 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
         Ok(writer.write_all(&self.trailer)?)
     }
 
+    /// Check a command against the constraints of the binary encoding, the same ones
+    /// `save_data` enforces: non-empty collections (at most 64 for outlines),
+    /// valid color indices, and units representable in the coordinate range.
+    pub fn validate_command(&self, cmd: &Command) -> Result<()> {
+        let invalid = |msg| Err(TVGError { kind: ErrorKind::OutOfRange, msg });
+        let count = |len: usize, max: usize| if (1..=max).contains(&len) { Ok(()) } else {
+            invalid("empty or oversized collection") };
+
+        let unit  = |val: Unit| self.check_unit(val);
+        let point = |pt: &Point| -> Result<()> { unit(pt.x)?; unit(pt.y) };
+        let line  = |ln: &Line | -> Result<()> { point(&ln.start)?; point(&ln.end) };
+        let rect  = |rc: &Rect | -> Result<()> {
+            unit(rc.x)?; unit(rc.y)?; unit(rc.w)?; unit(rc.h) };
+
+        let style = |style: &Style| -> Result<()> {
+            let colors = self.color_table.len() as u32;   match style {
+                Style::FlatColor(idx) if *idx < colors => Ok(()),
+                Style::LinearGradient { points, cindex } |
+                Style::RadialGradient { points, cindex }
+                    if cindex.0 < colors && cindex.1 < colors => {
+                    point(&points.0)?;  point(&points.1) }
+                _ => invalid("invalid color index"),
+            }
+        };
+
+        let path = |coll: &[Segment]| coll.iter().try_for_each(|seg| {
+            count(seg.cmds.len(), usize::MAX)?;     point(&seg.start)?;
+            seg.cmds.iter().try_for_each(|cmd| {
+                if let Some(val) = cmd.lwidth { unit(val)? }
+                match &cmd.instr {
+                    SegInstr::Line  { end } => point(end),
+                    SegInstr::HLine { x } => unit(*x),
+                    SegInstr::VLine { y } => unit(*y),
+                    SegInstr::CubicBezier { ctrl, end } => {
+                        point(&ctrl.0)?; point(&ctrl.1)?; point(end) }
+                    SegInstr::ArcCircle  { radius, end, .. } => { unit(*radius)?; point(end) }
+                    SegInstr::ArcEllipse { radii, rotation, end, .. } => {
+                        unit(radii.0)?; unit(radii.1)?; unit(*rotation)?; point(end) }
+                    SegInstr::QuadBezier { ctrl, end } => { point(ctrl)?; point(end) }
+                    SegInstr::ClosePath => Ok(()),
+                }
+            })
+        });
+
+        match cmd {     Command::EndOfDocument => invalid("not a drawing command"),
+            Command::FillPolyg(FillCMD { fill, coll }) => { style(fill)?;
+                count(coll.len(), usize::MAX)?; coll.iter().try_for_each(point) }
+            Command::FillRects(FillCMD { fill, coll }) => { style(fill)?;
+                count(coll.len(), usize::MAX)?; coll.iter().try_for_each(rect) }
+            Command::FillPath (FillCMD { fill, coll }) => { style(fill)?;
+                count(coll.len(), usize::MAX)?; path(coll) }
+
+            Command::DrawLines(DrawCMD { line: ls, lwidth, coll }) => {
+                style(ls)?; unit(*lwidth)?;
+                count(coll.len(), usize::MAX)?; coll.iter().try_for_each(line) }
+            Command::DrawLoop (DrawCMD { line, lwidth, coll }, _) => {
+                style(line)?; unit(*lwidth)?;
+                count(coll.len(), usize::MAX)?; coll.iter().try_for_each(point) }
+            Command::DrawPath (DrawCMD { line, lwidth, coll }) => {
+                style(line)?; unit(*lwidth)?;   count(coll.len(), usize::MAX)?; path(coll) }
+
+            Command::OutlinePolyg(fill, DrawCMD { line, lwidth, coll }) => {
+                style(fill)?; style(line)?; unit(*lwidth)?;
+                count(coll.len(), 1 << 6)?; coll.iter().try_for_each(point) }
+            Command::OutlineRects(fill, DrawCMD { line, lwidth, coll }) => {
+                style(fill)?; style(line)?; unit(*lwidth)?;
+                count(coll.len(), 1 << 6)?; coll.iter().try_for_each(rect) }
+            Command::OutlinePath (fill, DrawCMD { line, lwidth, coll }) => {
+                style(fill)?; style(line)?; unit(*lwidth)?;
+                count(coll.len(), 1 << 6)?; path(coll) }
+        }
+    }
+
+    fn check_unit(&self, val: Unit) -> Result<()> {
+        let scaled = (val * (1u32 << self.header.scale) as f32).round() as i32;
+        match self.header.coord_range {
+            CoordinateRange::Default  => { i16::try_from(scaled)?; }
+            CoordinateRange::Reduced  => { i8 ::try_from(scaled)?; }
+            CoordinateRange::Enhanced => (),
+        }   Ok(())
+    }
+
     fn write_command(&self, cmd: &Command, writer: &mut W)-> Result<()> {
+        self.validate_command(cmd)?;
         match cmd {     Command::EndOfDocument => Ok(()),
             Command::FillPolyg(cmd) =>
                 self.write_fillcmd(1, cmd, writer, Self::write_point),
@@ -372,7 +462,6 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
                 self.write_fillcmd(2, cmd, writer, Self::write_rect),
 
             Command::FillPath(cmd) => {
-                debug_assert!(!cmd.coll.is_empty());
                 writer.write_u8((cmd.fill.to_u8() << 6) | 3)?;
                 writer.write_varuint(cmd.coll.len() as u32 - 1)?;
                 self.write_style(&cmd.fill, writer)?;
@@ -386,7 +475,6 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
                 self.write_drawcmd(5, cmd, writer, Self::write_point) },
 
             Command::DrawPath(cmd) => {
-                debug_assert!(!cmd.coll.is_empty());
                 writer.write_u8((cmd.line.to_u8() << 6) | 7)?;
                 writer.write_varuint(cmd.coll.len() as u32 - 1)?;
                 self.write_style(&cmd.line, writer)?;
@@ -400,7 +488,6 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
                 self.write_outline(9, fill, cmd, writer, Self::write_rect),
 
             Command::OutlinePath (fill, cmd) => {
-                debug_assert!((1..=1 << 6).contains(&cmd.coll.len()));
                 writer.write_u8( (fill.to_u8() << 6) | 10)?;
                 writer.write_u8((cmd.line.to_u8() << 6) | (cmd.coll.len() as u8 - 1))?;
                 self.write_style( fill, writer)?;       self.write_style(&cmd.line, writer)?;
@@ -411,7 +498,6 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
 
     fn write_fillcmd<T>(&self, idx: u8, cmd: &FillCMD<T>, writer: &mut W,
         write_fn: impl Fn(&Self, &T, &mut W) -> Result<()>) -> Result<()> {
-        debug_assert!(!cmd.coll.is_empty());
         writer.write_u8((cmd.fill.to_u8() << 6) | idx)?;
         writer.write_varuint(cmd.coll.len() as u32 - 1)?;
         self.write_style(&cmd.fill, writer)?;
@@ -420,7 +506,6 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
 
     fn write_drawcmd<T>(&self, idx: u8, cmd: &DrawCMD<T>, writer: &mut W,
         write_fn: impl Fn(&Self, &T, &mut W) -> Result<()>) -> Result<()> {
-        debug_assert!(!cmd.coll.is_empty());
         writer.write_u8((cmd.line.to_u8() << 6) | idx)?;
         writer.write_varuint(cmd.coll.len() as u32 - 1)?;
         self.write_style(&cmd.line, writer)?;   self.write_unit(cmd.lwidth, writer)?;
@@ -429,7 +514,6 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
 
     fn write_outline<T>(&self, idx: u8, fill: &Style, cmd: &DrawCMD<T>, writer: &mut W,
         write_fn: impl Fn(&Self, &T, &mut W) -> Result<()>) -> Result<()> {
-        debug_assert!((1..=1 << 6).contains(&cmd.coll.len()));
         writer.write_u8((fill.to_u8() << 6) | idx)?;
         writer.write_u8((cmd.line.to_u8() << 6) | (cmd.coll.len() as u8 - 1))?;
         self.write_style(fill, writer)?;        self.write_style(&cmd.line, writer)?;
@@ -531,9 +615,68 @@ impl<R: io::Read, W: io::Write> TinyVG<R, W> { #[allow(clippy::new_without_defau
     }
 }
 
+/// **JSON schema** (feature `"serde"`), stable and one-to-one with the in-memory model:
+///
+/// ```json
+/// { "header": { "scale": 2, "color_fmt": "u8888|u565|f32",
+///               "coord_range": "default|reduced|enhanced", "width": 24, "height": 24 },
+///   "color_table": [ { "r": 41, "g": 173, "b": 255, "a": 255 } ],
+///   "commands": [
+///     { "fill_path": { "fill": { "flat_color": 0 }, "coll": [
+///         { "start": { "x": 12.0, "y": 1.0 }, "cmds": [
+///             { "instr": { "line": { "end": { "x": 3.0, "y": 5.0 } } } },
+///             { "instr": { "v_line": { "y": 11.0 } }, "lwidth": 1.5 },
+///             { "instr": "close_path" } ] } ] } },
+///     { "outline_rects": [ { "linear_gradient": {
+///           "points": [ { "x": 0.0, "y": 0.0 }, { "x": 8.0, "y": 8.0 } ], "cindex": [0, 1] } },
+///         { "line": { "radial_gradient": { ... } }, "lwidth": 1.0, "coll": [
+///             { "x": 1.0, "y": 1.0, "w": 6.0, "h": 6.0 } ] } ] },
+///     { "draw_loop": [ { "line": { "flat_color": 1 }, "lwidth": 0.5, "coll": [...] }, false ] }
+///   ],
+///   "trailer": [] }
+/// ```
+///
+/// Command names are `fill_polyg`, `fill_rects`, `fill_path`, `draw_lines`, `draw_loop`
+/// (the flag is `strip`), `draw_path`, `outline_polyg`, `outline_rects` and `outline_path`,
+/// path instructions are `line`, `h_line`, `v_line`, `cubic_bezier`, `arc_circle`,
+/// `arc_ellipse`, `quad_bezier` and `close_path`, an absent `lwidth` means no change.
+#[cfg(feature = "serde")] impl<R: io::Read, W: io::Write> TinyVG<R, W> {
+    pub fn to_json(&self) -> serde_json::Result<String> { serde_json::to_string_pretty(self) }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> { serde_json::from_str(json) }
+}
+
+#[cfg(feature = "serde")]
+impl<R: io::Read, W: io::Write> serde::Serialize for TinyVG<R, W> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) ->
+        std::result::Result<S::Ok, S::Error> {  use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("TinyVG", 4)?;
+        state.serialize_field("header", &self.header)?;
+        state.serialize_field("color_table", &self.color_table)?;
+        state.serialize_field("commands", &self.commands)?;
+        state.serialize_field("trailer",  &self.trailer)?;     state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, R: io::Read, W: io::Write> serde::Deserialize<'de> for TinyVG<R, W> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) ->
+        std::result::Result<Self, D::Error> {
+        #[derive(serde::Deserialize)] struct Document { header: Header,
+            color_table: Vec<RGBA8888>, commands: Vec<Command>,
+            #[serde(default)] trailer: Vec<u8>,
+        }
+
+        let doc = Document::deserialize(deserializer)?;
+        let mut tvgd = Self::new();     tvgd.set_coord_range(doc.header.coord_range);
+        (tvgd.header, tvgd.color_table, tvgd.commands, tvgd.trailer) =
+            (doc.header, doc.color_table, doc.commands, doc.trailer);   Ok(tvgd)
+    }
+}
+
 const TVG_MAGIC: u16  = 0x5672; // [0x72, 0x56];
 const TVG_VERSION: u8 = 1;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug)] pub struct Header {
     //magic: u16,     // Must be [0x72, 0x56], 0x5672
     //version: u8,    // Must be 1. This field might decide how the rest of the format looks like.
@@ -583,7 +726,7 @@ type VarUInt = u32;
 //#[derive(Clone, Copy)] struct Unit(f32);
 type Unit = f32;    // Each Unit takes up 16/8/32 bits, // XXX: can be fixed-point?
 
-trait TVGRead: io::Read  {
+pub(crate) trait TVGRead: io::Read  {
     /*#[inline] fn read_value<T>(&mut self) -> io::Result<T> {    // XXX:
         let mut buf = [0; core::mem::size_of::<T>()];
         self.read_exact(&mut buf)?; Ok(T::from_le_bytes(buf))
@@ -612,7 +755,7 @@ trait TVGRead: io::Read  {
     }
 }
 
-trait TVGWrite: io::Write {
+pub(crate) trait TVGWrite: io::Write {
     /*#[inline] fn write_value<T>(&mut self, n: T) ->
         io::Result<()> { self.write_all(&n.to_le_bytes()) }
     }*/
@@ -634,11 +777,19 @@ trait TVGWrite: io::Write {
 impl<W: io::Write> TVGWrite for W {}
 impl<R: io::Read>  TVGRead  for R {}
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase"))]
 #[derive(Debug, Clone, Copy)] pub enum CoordinateRange { Default = 0, Reduced = 1, Enhanced = 2 }
-#[derive(Debug, Clone, Copy)] pub enum ColorEncoding { RGBA8888 = 0, RGB565 = 1, RGBAf32 = 2, }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)] pub enum ColorEncoding {
+    #[cfg_attr(feature = "serde", serde(rename = "u8888"))] RGBA8888 = 0,
+    #[cfg_attr(feature = "serde", serde(rename = "u565"))]  RGB565 = 1,
+    #[cfg_attr(feature = "serde", serde(rename = "f32"))]   RGBAf32 = 2,
+}
 
 //#[derive(Clone, Copy)] struct RGB565(u16);     // sRGB color space
-#[derive(Clone, Copy)] pub struct RGBA8888 { pub r:  u8, pub g:  u8, pub b:  u8, pub a:  u8 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)] pub struct RGBA8888 { pub r:  u8, pub g:  u8, pub b:  u8, pub a:  u8 }
 //struct RGBAf32  { r: f32, g: f32, b: f32, a: f32 }  // scRGB color space
 // color channel between 0 and 100% intensity, mapped to value range
 //use tiny_skia::{ColorU8, Color, Rect, Point};     // XXX: tiny_skia_path
@@ -653,6 +804,8 @@ impl<R: io::Read>  TVGRead  for R {}
 /// --------------- | ---- | -------------------------------------------------------
 /// command_index   | u6   | The command that is encoded next. See table above.
 /// prim_style_kind | u2   | The type of style this command uses as a primary style.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case"))]
 pub enum Command { EndOfDocument,
     FillPolyg(FillCMD<Point>), FillRects(FillCMD<Rect>), FillPath(FillCMD<Segment>),
     DrawLines(DrawCMD<Line>),  DrawLoop (DrawCMD<Point>, bool), //DrawStrip(DrawCMD<Point>),
@@ -660,11 +813,15 @@ pub enum Command { EndOfDocument,
     OutlineRects(Style, DrawCMD<Rect>), OutlinePath (Style, DrawCMD<Segment>),
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FillCMD<T> { pub fill: Style, pub coll: Vec<T> }     // line -> stroke
 /// Each line is line_width units wide, and at least a single display pixel.
 /// This means that line_width of 0 is still visible, even though only marginally.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DrawCMD<T> { pub line: Style, pub lwidth: Unit, pub coll: Vec<T> }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case"))]
 pub enum Style { FlatColor(VarUInt),   // color_index in the color_table
     LinearGradient { points: (Point, Point), cindex: (VarUInt, VarUInt), },
     /// The gradient is formed by a mental circle with the center at point_0 and
@@ -679,6 +836,7 @@ impl Style {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line { pub start: Point, pub end: Point, }
 
 /// **Point**: Points are a X and Y coordinate pair.
@@ -696,7 +854,9 @@ pub struct Line { pub start: Point, pub end: Point, }
 /// The file header defines a _scale_ by which each signed integer is divided into
 /// the final value. For example, with a _reduced_ value of 0x13 and a scale of 4,
 /// we get the final value of 1.1875, as the number is interpreted as binary b0001.0011.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy)] pub struct Point { pub x: Unit, pub y: Unit }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy)] pub struct Rect  { pub x: Unit, pub y: Unit, pub w: Unit, pub h: Unit }
 
 /// **Paths** describe instructions to create complex 2D graphics.
@@ -722,10 +882,18 @@ pub struct Line { pub start: Point, pub end: Point, }
 ///     4) If a line width is present, that line width is read as a Unit
 ///
 ///     5) The data for this command is decoded.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Segment { pub start: Point, pub cmds: Vec<SegmentCommand>, }
 
-pub struct SegmentCommand { pub instr: SegInstr, pub lwidth: Option<Unit>, }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentCommand { pub instr: SegInstr,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub lwidth: Option<Unit>,
+}
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case"))]
+/// `sweep` of arcs is the inverse of SVG's sweep-flag, as the reference SDK draws them.
 pub enum SegInstr { //Move { end: Point },
     Line { end: Point, }, HLine { x: Unit, }, VLine { y: Unit, },
     CubicBezier { ctrl: (Point, Point), end: Point, },
@@ -734,6 +902,96 @@ pub enum SegInstr { //Move { end: Point },
     QuadBezier { ctrl: Point, end: Point, },     ClosePath,
 }
 
+impl Segment {
+    /// Append this segment to a kurbo path, arcs are approximated by cubic Béziers.
+    /// Returns the maximum line width changed within this segment.
+    pub fn append_to(&self, path: &mut kurbo::BezPath) -> Option<Unit> {
+        let kpt = |x: Unit, y: Unit| kurbo::Point::new(x as _, y as _);
+        let (start, mut lwidth) = (kpt(self.start.x, self.start.y), None::<Unit>);
+        let mut last = start;   path.move_to(start);
+
+        for cmd in &self.cmds {
+            if let Some(width) = cmd.lwidth {
+                lwidth = Some(lwidth.map_or(width, |lw| lw.max(width))) }
+            let arc_to = |path: &mut kurbo::BezPath, radii: (Unit, Unit),
+                rotation: Unit, large: bool, sweep: bool, end| {
+                let svg_arc = kurbo::SvgArc { from: last, to: end, large_arc: large, sweep: !sweep,
+                    radii: kurbo::Vec2::new(radii.0 as _, radii.1 as _),
+                    x_rotation: (rotation as f64).to_radians(),
+                };
+
+                if let Some(arc) = kurbo::Arc::from_svg_arc(&svg_arc) {
+                    arc.to_cubic_beziers(0.1, |p1, p2, p| path.curve_to(p1, p2, p))
+                } else { path.line_to(end) }
+            };
+
+            match &cmd.instr {
+                SegInstr::Line  { end } => { last = kpt(end.x, end.y);  path.line_to(last) }
+                SegInstr::HLine { x } => { last.x = *x as _;  path.line_to(last) }
+                SegInstr::VLine { y } => { last.y = *y as _;  path.line_to(last) }
+                SegInstr::CubicBezier { ctrl, end } => {  let p = kpt(end.x, end.y);
+                    path.curve_to(kpt(ctrl.0.x, ctrl.0.y), kpt(ctrl.1.x, ctrl.1.y), p);  last = p;
+                }
+                SegInstr::QuadBezier { ctrl, end } => {   let p = kpt(end.x, end.y);
+                    path.quad_to(kpt(ctrl.x, ctrl.y), p);   last = p;
+                }
+                SegInstr::ArcCircle  { large, sweep, radius, end } => {
+                    let p = kpt(end.x, end.y);
+                    arc_to(path, (*radius, *radius), 0.0, *large, *sweep, p);   last = p;
+                }
+                SegInstr::ArcEllipse { large, sweep, radii, rotation, end } => {
+                    let p = kpt(end.x, end.y);
+                    arc_to(path, *radii, *rotation, *large, *sweep, p);     last = p;
+                }
+                SegInstr::ClosePath => { path.close_path();  last = start; }
+            }
+        }   lwidth
+    }
+}
+
+impl Command {
+    /// Bounding box of the command in image coordinates, including half the line width.
+    pub fn bounds(&self) -> Option<Rect> {   use kurbo::Shape;
+        let (mut path, mut lwidth) = (kurbo::BezPath::new(), 0.0);
+        let kpt = |pt: &Point| kurbo::Point::new(pt.x as _, pt.y as _);
+
+        let polyg = |path: &mut kurbo::BezPath, coll: &[Point]| {
+            let mut iter = coll.iter();
+            if let Some(pt) = iter.next() { path.move_to(kpt(pt)); path.line_to(kpt(pt)); }
+            iter.for_each(|pt| path.line_to(kpt(pt)));
+        };
+        let rects = |path: &mut kurbo::BezPath, coll: &[Rect]| for rect in coll {
+            path.extend(kurbo::Rect::new(rect.x as _, rect.y as _,
+                (rect.x + rect.w) as _, (rect.y + rect.h) as _).path_elements(0.1));
+        };
+        let segs  = |path: &mut kurbo::BezPath, coll: &[Segment], lwidth: Unit|
+            coll.iter().fold(lwidth, |lw, seg| seg.append_to(path).map_or(lw, |w| lw.max(w)));
+
+        match self {    Command::EndOfDocument => return None,
+            Command::FillPolyg(FillCMD { coll, .. }) => polyg(&mut path, coll),
+            Command::FillRects(FillCMD { coll, .. }) => rects(&mut path, coll),
+            Command::FillPath (FillCMD { coll, .. }) => { segs(&mut path, coll, 0.0); }
+            Command::DrawLines(DrawCMD { lwidth: lw, coll, .. }) => {
+                for line in coll { path.move_to(kpt(&line.start)); path.line_to(kpt(&line.end)); }
+                lwidth = *lw;
+            }
+            Command::DrawLoop (DrawCMD { lwidth: lw, coll, .. }, _) |
+            Command::OutlinePolyg(_, DrawCMD { lwidth: lw, coll, .. }) => {
+                polyg(&mut path, coll);  lwidth = *lw; }
+            Command::OutlineRects(_, DrawCMD { lwidth: lw, coll, .. }) => {
+                rects(&mut path, coll);  lwidth = *lw; }
+            Command::DrawPath (DrawCMD { lwidth: lw, coll, .. }) |
+            Command::OutlinePath (_, DrawCMD { lwidth: lw, coll, .. }) =>
+                lwidth = segs(&mut path, coll, *lw),
+        }
+
+        if path.elements().is_empty() { return None }
+        let bbox = path.bounding_box().inflate(lwidth as f64 / 2.0, lwidth as f64 / 2.0);
+        Some(Rect { x: bbox.x0 as _, y: bbox.y0 as _,
+                    w: bbox.width() as _, h: bbox.height() as _ })
+    }
+}
+
 #[cfg(test)] mod tests { use super::*;
     type TestTVG = TinyVG<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>>;
 
@@ -0,0 +1,342 @@
+
+use crate::tinyvg::*;
+use std::{io::{self, Read}, ops::Range};
+
+/// **Trailer extensions**: tagged chunks stored after `EndOfDocument`, which the spec
+/// leaves free for arbitrary data, so plain TinyVG readers are unaffected.
+///
+/// Field   | Type      | Description
+/// ------- | --------- | -------------------------------------------------------------
+/// magic   | [u8; 4]   | Must be `b"TVGx"`.
+/// length  | VarUInt   | Total byte size of the chunks that follow.
+/// chunks  | Chunk[]   | Each is a 4-byte tag, a VarUInt payload size and the payload.
+///
+/// Anything not matching this layout exactly is kept verbatim as foreign data,
+/// which is written back after the chunks.
+#[derive(Debug, Default, Clone)] pub struct Trailer {
+    pub chunks: Vec<Chunk>,
+    pub foreign: Vec<u8>,   // unrecognized trailer data, preserved as is
+}
+
+#[derive(Debug, Clone)] pub struct Chunk { pub tag: [u8; 4], pub data: Vec<u8> }
+
+const TRAILER_MAGIC: &[u8; 4] = b"TVGx";
+
+impl Trailer {
+    pub fn parse(data: &[u8]) -> Self {
+        Self::parse_chunks(data).unwrap_or_else(||
+            Self { chunks: vec![], foreign: data.to_vec() })
+    }
+
+    fn parse_chunks(data: &[u8]) -> Option<Self> {
+        let mut reader = data.strip_prefix(TRAILER_MAGIC)?;
+        let len = reader.read_varuint().ok()? as usize;
+        if reader.len() < len { return None }
+
+        let (mut body, foreign) = reader.split_at(len);
+        let mut chunks = vec![];
+        while !body.is_empty() {
+            let mut tag = [0; 4];   body.read_exact(&mut tag).ok()?;
+            let len = body.read_varuint().ok()? as usize;
+            if body.len() < len { return None }
+            let (data, rest) = body.split_at(len);
+            chunks.push(Chunk { tag, data: data.to_vec() });  body = rest;
+        }   Some(Self { chunks, foreign: foreign.to_vec() })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        if self.chunks.is_empty() { return self.foreign.clone() }
+        let mut body = vec![];
+        for chunk in &self.chunks {     // writing into Vec never fails
+            body.extend_from_slice(&chunk.tag);
+            let _ = body.write_varuint(chunk.data.len() as _);
+            body.extend_from_slice(&chunk.data);
+        }
+
+        let mut data = TRAILER_MAGIC.to_vec();
+        let _ = data.write_varuint(body.len() as _);
+        data.extend_from_slice(&body);  data.extend_from_slice(&self.foreign);  data
+    }
+
+    pub fn chunk(&self, tag: &[u8; 4]) -> Option<&[u8]> {
+        self.chunks.iter().find(|chunk| &chunk.tag == tag).map(|chunk| &chunk.data[..])
+    }
+
+    /// Replace or append the chunk with `tag`, remove it if `data` is empty.
+    pub fn set_chunk(&mut self, tag: &[u8; 4], data: Vec<u8>) {
+        let pos = self.chunks.iter().position(|chunk| &chunk.tag == tag);
+        match (pos, data.is_empty()) {
+            (Some(idx), true) => { self.chunks.remove(idx); }
+            (Some(idx), false) => self.chunks[idx].data = data,
+            (None, false) => self.chunks.push(Chunk { tag: *tag, data }),
+            (None, true) => (),
+        }
+    }
+}
+
+impl<R: io::Read, W: io::Write> TinyVG<R, W> {
+    pub fn trailer_chunk(&self, tag: &[u8; 4]) -> Option<Vec<u8>> {
+        Trailer::parse(&self.trailer).chunk(tag).map(|data| data.to_vec())
+    }
+    pub fn set_trailer_chunk(&mut self, tag: &[u8; 4], data: Vec<u8>) {
+        let mut trailer = Trailer::parse(&self.trailer);
+        trailer.set_chunk(tag, data);   self.trailer = trailer.to_bytes();
+    }
+
+    /// Metadata stored in the `META` trailer chunk, empty if there is none.
+    pub fn metadata(&self) -> Metadata {
+        self.trailer_chunk(Metadata::TAG).and_then(|data|
+            Metadata::from_bytes(&data)).unwrap_or_default()
+    }
+    pub fn set_metadata(&mut self, meta: &Metadata) {
+        self.set_trailer_chunk(Metadata::TAG, meta.to_bytes())
+    }
+}
+
+/// Key/value metadata, each entry is a pair of UTF-8 strings prefixed by a VarUInt length.
+/// Well known keys have typed accessors, other keys are kept in order.
+#[derive(Debug, Default, Clone, PartialEq)] pub struct Metadata {
+    pub entries: Vec<(String, String)>,
+}
+
+impl Metadata {
+    pub const TAG: &'static [u8; 4] = b"META";
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        if let Some(entry) = self.entries.iter_mut().find(|(k, _)| k == key) {
+            entry.1 = value } else { self.entries.push((key.to_owned(), value)) }
+    }
+    pub fn remove(&mut self, key: &str) { self.entries.retain(|(k, _)| k != key) }
+
+    pub fn title(&self)  -> Option<&str> { self.get("title") }
+    pub fn author(&self) -> Option<&str> { self.get("author") }
+    pub fn license(&self) -> Option<&str> { self.get("license") }
+    pub fn description(&self) -> Option<&str> { self.get("description") }
+    /// File name of the SVG this image was converted from
+    pub fn source(&self) -> Option<&str> { self.get("source") }
+    /// Intended display resolution, in dots per inch
+    pub fn dpi(&self) -> Option<f32> { self.get("dpi")?.parse().ok() }
+
+    pub fn set_title(&mut self, title: &str) { self.set("title", title) }
+    pub fn set_author(&mut self, author: &str) { self.set("author", author) }
+    pub fn set_license(&mut self, license: &str) { self.set("license", license) }
+    pub fn set_description(&mut self, desc: &str) { self.set("description", desc) }
+    pub fn set_source(&mut self, source: &str) { self.set("source", source) }
+    pub fn set_dpi(&mut self, dpi: f32) { self.set("dpi", dpi.to_string()) }
+
+    pub fn from_bytes(mut data: &[u8]) -> Option<Self> {
+        let mut entries = vec![];
+        while !data.is_empty() { entries.push((read_str(&mut data)?, read_str(&mut data)?)); }
+        Some(Self { entries })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = vec![];
+        for (key, value) in &self.entries {
+            write_str(&mut data, key);  write_str(&mut data, value);
+        }   data
+    }
+}
+
+/// A named run of consecutive commands, e.g. converted from an SVG element with `id`.
+/// `depth` is the group nesting level of the element, 0 for top-level ones.
+#[derive(Debug, Clone, PartialEq)] pub struct NamedRange {
+    pub name: String, pub range: Range<usize>, pub depth: u32,
+}
+
+impl NamedRange {
+    pub const TAG: &'static [u8; 4] = b"NAME";
+
+    /// Each entry is a string (VarUInt length + UTF-8) followed by
+    /// VarUInt start index, command count and nesting depth.
+    pub fn from_bytes(mut data: &[u8]) -> Option<Vec<Self>> {
+        let mut coll = vec![];
+        while !data.is_empty() {
+            let name = read_str(&mut data)?;
+            let start = data.read_varuint().ok()? as usize;
+            let count = data.read_varuint().ok()? as usize;
+            coll.push(Self { name, range: start..start.checked_add(count)?,
+                depth: data.read_varuint().ok()? });
+        }   Some(coll)
+    }
+
+    pub fn to_bytes(coll: &[Self]) -> Vec<u8> {
+        let mut data = vec![];
+        for nr in coll {    write_str(&mut data, &nr.name);
+            let _ = data.write_varuint(nr.range.start as _);
+            let _ = data.write_varuint(nr.range.len() as _);
+            let _ = data.write_varuint(nr.depth);
+        }   data
+    }
+}
+
+impl<R: io::Read, W: io::Write> TinyVG<R, W> {
+    pub fn named_ranges(&self) -> Vec<NamedRange> {
+        self.trailer_chunk(NamedRange::TAG).and_then(|data|
+            NamedRange::from_bytes(&data)).unwrap_or_default()
+    }
+    pub fn set_named_ranges(&mut self, coll: &[NamedRange]) {
+        self.set_trailer_chunk(NamedRange::TAG, NamedRange::to_bytes(coll))
+    }
+
+    pub fn lookup_name(&self, name: &str) -> Option<Range<usize>> {
+        self.named_ranges().into_iter().find(|nr| nr.name == name)
+            .map(|nr| nr.range).filter(|range| range.end <= self.commands.len())
+    }
+    pub fn commands_by_name(&self, name: &str) -> Option<&[Command]> {
+        self.lookup_name(name).map(|range| &self.commands[range])
+    }
+
+    /// HTML `<map>` with a rectangular hotspot for each named range at `scale`,
+    /// deeper (more specific) elements come first as browsers pick the first match.
+    pub fn html_image_map(&self, map_name: &str, scale: f32) -> String {
+        let mut coll = self.named_ranges();
+        coll.sort_by_key(|nr| std::cmp::Reverse(nr.depth));
+
+        let mut html = format!("<map name=\"{}\">\n", html_escape(map_name));
+        for nr in coll {
+            let Some(cmds) = self.commands.get(nr.range) else { continue };
+            let Some((x0, y0, x1, y1)) = cmds.iter().filter_map(Command::bounds)
+                .map(|r| (r.x, r.y, r.x + r.w, r.y + r.h)).reduce(|a, b|
+                (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))) else { continue };
+
+            let name = html_escape(&nr.name);
+            html.push_str(&format!("  <area shape=\"rect\" coords=\"{},{},{},{}\" \
+                href=\"#{name}\" alt=\"{name}\" title=\"{name}\">\n",
+                (x0 * scale).floor() as i32, (y0 * scale).floor() as i32,
+                (x1 * scale).ceil()  as i32, (y1 * scale).ceil()  as i32));
+        }   html.push_str("</map>\n");     html
+    }
+}
+
+/// A named layer made of command ranges, commands may be switched on and off by layer
+/// at render time, those not assigned to any layer are always visible.
+#[derive(Debug, Clone, PartialEq)] pub struct Layer {
+    pub name: String, pub ranges: Vec<Range<usize>>,
+}
+
+impl Layer {
+    pub const TAG: &'static [u8; 4] = b"LAYR";
+
+    /// Each entry is a string (VarUInt length + UTF-8), a VarUInt count of ranges,
+    /// then VarUInt start index and command count of each range.
+    pub fn from_bytes(mut data: &[u8]) -> Option<Vec<Self>> {
+        let mut coll = vec![];
+        while !data.is_empty() {
+            let name = read_str(&mut data)?;
+            let count = data.read_varuint().ok()?;
+            let ranges = (0..count).map(|_| {
+                let start = data.read_varuint().ok()? as usize;
+                Some(start..start.checked_add(data.read_varuint().ok()? as _)?)
+            }).collect::<Option<Vec<_>>>()?;    coll.push(Self { name, ranges });
+        }   Some(coll)
+    }
+
+    pub fn to_bytes(coll: &[Self]) -> Vec<u8> {
+        let mut data = vec![];
+        for layer in coll {     write_str(&mut data, &layer.name);
+            let _ = data.write_varuint(layer.ranges.len() as _);
+            for range in &layer.ranges {
+                let _ = data.write_varuint(range.start as _);
+                let _ = data.write_varuint(range.len() as _);
+            }
+        }   data
+    }
+}
+
+impl<R: io::Read, W: io::Write> TinyVG<R, W> {
+    pub fn layers(&self) -> Vec<Layer> {
+        self.trailer_chunk(Layer::TAG).and_then(|data|
+            Layer::from_bytes(&data)).unwrap_or_default()
+    }
+    pub fn set_layers(&mut self, coll: &[Layer]) {
+        self.set_trailer_chunk(Layer::TAG, Layer::to_bytes(coll))
+    }
+
+    /// Per-command visibility with only the `visible` layers switched on,
+    /// all commands are visible if it's `None`.
+    pub fn layer_mask(&self, visible: Option<&[&str]>) -> Vec<bool> {
+        let mut mask = vec![true; self.commands.len()];
+        let Some(visible) = visible else { return mask };
+        let (shown, hidden): (Vec<_>, Vec<_>) = self.layers().into_iter()
+            .partition(|layer| visible.contains(&layer.name.as_str()));
+
+        for (layer, flag) in hidden.iter().map(|layer| (layer, false))
+            .chain(shown.iter().map(|layer| (layer, true))) {
+            for range in &layer.ranges {
+                let end = range.end.min(mask.len());
+                if let Some(mask) = mask.get_mut(range.start..end) { mask.fill(flag) }
+            }
+        }   mask
+    }
+}
+
+pub(crate) fn html_escape(str: &str) -> String {
+    str.chars().fold(String::new(), |mut out, c| { match c {
+        '&' => out.push_str("&amp;"), '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"), '"' => out.push_str("&quot;"), _ => out.push(c),
+    };  out })
+}
+
+fn read_str(data: &mut &[u8]) -> Option<String> {
+    let len = data.read_varuint().ok()? as usize;
+    if data.len() < len { return None }
+    let (str, rest) = data.split_at(len);   *data = rest;
+    String::from_utf8(str.to_vec()).ok()
+}
+
+fn write_str(data: &mut Vec<u8>, str: &str) {
+    let _ = data.write_varuint(str.len() as _);     data.extend_from_slice(str.as_bytes());
+}
+
+#[cfg(test)] mod tests { use super::*;
+    type TestTVG = TinyVG<io::Cursor<Vec<u8>>, io::Cursor<Vec<u8>>>;
+
+    #[test] fn metadata_roundtrip_keeps_foreign_data() {
+        let mut image = TestTVG::new();     image.trailer = b"garbage".to_vec();
+        assert!(image.metadata().entries.is_empty());
+
+        let mut meta = Metadata::default();
+        meta.set_title("Tiger");    meta.set_dpi(96.0);     meta.set("x-tool", "intvg");
+        image.set_metadata(&meta);
+
+        assert!(image.trailer.starts_with(TRAILER_MAGIC) && image.trailer.ends_with(b"garbage"));
+        let meta = image.metadata();
+        assert_eq!((meta.title(), meta.dpi(), meta.get("x-tool")),
+                   (Some("Tiger"), Some(96.0), Some("intvg")));
+
+        image.set_metadata(&Metadata::default());
+        assert_eq!(image.trailer, b"garbage");
+    }
+
+    #[test] fn named_ranges_lookup_and_image_map() {
+        let mut image = TestTVG::new();     image.color_table.push(RGBA8888 {
+            r: 0, g: 0, b: 0, a: 255 });
+        image.commands.push(Command::FillRects(FillCMD { fill: Style::FlatColor(0),
+            coll: vec![Rect { x: 1.0, y: 2.0, w: 3.0, h: 4.0 }] }));
+
+        image.set_named_ranges(&[NamedRange { name: "eye".into(), range: 0..1, depth: 1 },
+            NamedRange { name: "bad".into(), range: 0..2, depth: 0 }]);
+        assert_eq!(image.lookup_name("eye"), Some(0..1));
+        assert!(image.commands_by_name("bad").is_none());
+
+        assert_eq!(image.html_image_map("tiger", 2.0), "<map name=\"tiger\">\n  <area \
+            shape=\"rect\" coords=\"2,4,8,12\" href=\"#eye\" alt=\"eye\" title=\"eye\">\n</map>\n");
+    }
+
+    #[test] fn layer_mask_switches_layers() {
+        let mut image = TestTVG::new();
+        for _ in 0..4 { image.commands.push(Command::EndOfDocument); }
+        image.set_layers(&[Layer { name: "base".into(), ranges: vec![0..1, 3..9] },
+                           Layer { name: "badge".into(), ranges: vec![1..2, 9..12] }]);
+
+        assert_eq!(image.layers()[0].ranges, [0..1, 3..9]);
+        assert_eq!(image.layer_mask(None), [true; 4]);
+        assert_eq!(image.layer_mask(Some(&["badge"])), [false, true, true, false]);
+        assert_eq!(image.layer_mask(Some(&[])), [false, false, true, false]);
+    }
+}
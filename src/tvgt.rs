@@ -0,0 +1,359 @@
+
+use crate::tinyvg::*;
+use std::{error::Error, io};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// **TinyVG text format** (.tvgt), the s-expression representation used by the
+/// reference SDK (`tvg-text`), mapping one-to-one onto `Command`/`Style`/`SegInstr`:
+///
+/// ```text
+/// (tvg 1
+///   (width height 1/scale u8888|u565|f32 default|reduced|enhanced)
+///   ( (r g b) (r g b a) ... )   // color table, channels in 0.0..=1.0
+///   ( (fill_path (flat 0) ( ((x y) ( (line - x y) (close -) )) )) ... )
+///   (trailer 0123abcd)          // optional, hex bytes, not in the reference SDK
+/// )
+/// ```
+///
+/// Style is `(flat c)`, `(linear (x y) (x y) c0 c1)` or `(radial (x y) (x y) c0 c1)`,
+/// the `-` after a path instruction name means no line width change.
+pub trait TextFormat {
+    fn load_text(text: &str) -> Result<Self> where Self: Sized;
+    fn save_text(&self, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl<R: io::Read, W: io::Write> TextFormat for TinyVG<R, W> {
+    fn load_text(text: &str) -> Result<Self> {
+        let mut tokens = Tokens { text, pos: 0 };
+        let root = parse_sexp(&mut tokens)?;
+        if tokens.next().is_some() { return Err("trailing data after `(tvg ...)'".into()) }
+
+        let (hdr, colors, cmds, rest) = match list(&root)? {
+            [Sexp::Atom("tvg"), Sexp::Atom("1"), hdr, colors, cmds, rest @ ..] =>
+                (hdr, colors, cmds, rest),
+            _ => return Err("expect `(tvg 1 (header) (colors) (commands))'".into()),
+        };
+
+        let mut tvg = Self::new();
+        let [width, height, scale, color_fmt, coord_range] = list(hdr)? else {
+            return Err("expect `(width height 1/scale format range)' header".into()) };
+        tvg.header.width  = atom(width)? .parse()?;
+        tvg.header.height = atom(height)?.parse()?;
+
+        let scale = atom(scale)?.strip_prefix("1/").ok_or("expect `1/scale'")?
+            .parse::<u32>()?;
+        if !scale.is_power_of_two() || 1 << 15 < scale {
+            return Err(format!("invalid scale: 1/{scale}").into()) }
+        tvg.header.scale = scale.trailing_zeros() as _;
+
+        tvg.header.color_fmt = match atom(color_fmt)? {
+            "u8888" => ColorEncoding::RGBA8888, "u565" => ColorEncoding::RGB565,
+            "f32"   => ColorEncoding::RGBAf32,
+            x => return Err(format!("unsupported color encoding: {x}").into()),
+        };
+        tvg.set_coord_range(match atom(coord_range)? {
+            "default"  => CoordinateRange::Default,
+            "reduced"  => CoordinateRange::Reduced,
+            "enhanced" => CoordinateRange::Enhanced,
+            x => return Err(format!("unsupported coordinate range: {x}").into()),
+        });
+
+        for color in list(colors)? {
+            let chan = |s: &Sexp| -> Result<u8> {
+                let v = unit(s)?;   if !(0.0..=1.0).contains(&v) {
+                    return Err(format!("color channel out of range: {v}").into()) }
+                Ok((v * 255.0 + 0.5) as _)
+            };
+            tvg.color_table.push(match list(color)? {
+                [r, g, b] => RGBA8888 { r: chan(r)?, g: chan(g)?, b: chan(b)?, a: 255 },
+                [r, g, b, a] =>
+                    RGBA8888 { r: chan(r)?, g: chan(g)?, b: chan(b)?, a: chan(a)? },
+                _ => return Err("expect `(r g b)' or `(r g b a)' color".into()),
+            });
+        }
+
+        for cmd in list(cmds)? { tvg.commands.push(parse_command(cmd)?); }
+
+        match rest {    [] => (),
+            [trailer] => match list(trailer)? {
+                [Sexp::Atom("trailer"), Sexp::Atom(hex)] => tvg.trailer = from_hex(hex)?,
+                _ => return Err("expect `(trailer hex)'".into()),
+            },
+            _ => return Err("unexpected elements after commands".into()),
+        }   Ok(tvg)
+    }
+
+    fn save_text(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let hdr = &self.header;
+        writeln!(writer, "(tvg 1\n  ({} {} 1/{} {} {})", hdr.width, hdr.height,
+            1u32 << hdr.scale, match hdr.color_fmt {   ColorEncoding::RGBA8888 => "u8888",
+                ColorEncoding::RGB565 => "u565", ColorEncoding::RGBAf32 => "f32", },
+            match hdr.coord_range { CoordinateRange::Default  => "default",
+                CoordinateRange::Reduced => "reduced", CoordinateRange::Enhanced => "enhanced",
+            })?;
+
+        writeln!(writer, "  (")?;
+        for c in &self.color_table {
+            let (r, g, b, a) = (c.r as f32 / 255.0, c.g as f32 / 255.0,
+                                c.b as f32 / 255.0, c.a as f32 / 255.0);
+            if c.a == 255 { writeln!(writer, "    ({r:.3} {g:.3} {b:.3})")?;
+            } else { writeln!(writer, "    ({r:.3} {g:.3} {b:.3} {a:.3})")?; }
+        }   writeln!(writer, "  )\n  (")?;
+
+        for cmd in &self.commands { write_command(writer, cmd)?; }
+        writeln!(writer, "  )")?;
+
+        if !self.trailer.is_empty() {
+            write!(writer, "  (trailer ")?;
+            for byte in &self.trailer { write!(writer, "{byte:02x}")?; }
+            writeln!(writer, ")")?;
+        }   writeln!(writer, ")")
+    }
+}
+
+fn write_command(writer: &mut dyn io::Write, cmd: &Command) -> io::Result<()> {
+    let ind = "      ";
+    let (name, fill, line, lwidth) = match cmd {
+        Command::EndOfDocument => return Ok(()),
+        Command::FillPolyg(cmd) => ("fill_polygon", Some(&cmd.fill), None, None),
+        Command::FillRects(cmd) => ("fill_rectangles", Some(&cmd.fill), None, None),
+        Command::FillPath (cmd) => ("fill_path", Some(&cmd.fill), None, None),
+        Command::DrawLines(cmd) => ("draw_lines", None, Some(&cmd.line), Some(cmd.lwidth)),
+        Command::DrawLoop (cmd, strip) => (if *strip { "draw_line_strip" } else {
+            "draw_line_loop" }, None, Some(&cmd.line), Some(cmd.lwidth)),
+        Command::DrawPath (cmd) => ("draw_line_path", None, Some(&cmd.line), Some(cmd.lwidth)),
+        Command::OutlinePolyg(fill, cmd) =>
+            ("outline_fill_polygon", Some(fill), Some(&cmd.line), Some(cmd.lwidth)),
+        Command::OutlineRects(fill, cmd) =>
+            ("outline_fill_rectangles", Some(fill), Some(&cmd.line), Some(cmd.lwidth)),
+        Command::OutlinePath (fill, cmd) =>
+            ("outline_fill_path", Some(fill), Some(&cmd.line), Some(cmd.lwidth)),
+    };
+
+    writeln!(writer, "    ({name}")?;
+    if let Some(fill) = fill { writeln!(writer, "{ind}{}", style_text(fill))?; }
+    if let Some(line) = line { writeln!(writer, "{ind}{}", style_text(line))?; }
+    if let Some(lwidth) = lwidth { writeln!(writer, "{ind}{lwidth}")?; }
+    writeln!(writer, "{ind}(")?;
+
+    let ind = "        ";
+    match cmd {     Command::EndOfDocument => (),
+        Command::FillPolyg(FillCMD { coll, .. }) | Command::DrawLoop(DrawCMD { coll, .. }, _) |
+        Command::OutlinePolyg(_, DrawCMD { coll, .. }) =>
+            for pt in coll { writeln!(writer, "{ind}{}", point_text(pt))?; },
+        Command::FillRects(FillCMD { coll, .. }) |
+        Command::OutlineRects(_, DrawCMD { coll, .. }) => for rect in coll {
+            writeln!(writer, "{ind}({} {} {} {})", rect.x, rect.y, rect.w, rect.h)?; },
+        Command::DrawLines(DrawCMD { coll, .. }) => for line in coll {
+            writeln!(writer, "{ind}({} {})", point_text(&line.start), point_text(&line.end))?;
+        },
+        Command::FillPath(FillCMD { coll, .. }) | Command::DrawPath(DrawCMD { coll, .. }) |
+        Command::OutlinePath(_, DrawCMD { coll, .. }) => for seg in coll {
+            writeln!(writer, "{ind}(\n{ind}  {}\n{ind}  (", point_text(&seg.start))?;
+            for cmd in &seg.cmds {
+                let lw = cmd.lwidth.map_or("-".to_owned(), |lw| lw.to_string());
+                writeln!(writer, "{ind}    ({})", match &cmd.instr {
+                    SegInstr::Line  { end } => format!("line {lw} {} {}", end.x, end.y),
+                    SegInstr::HLine { x } => format!("horiz {lw} {x}"),
+                    SegInstr::VLine { y } => format!("vert {lw} {y}"),
+                    SegInstr::CubicBezier { ctrl, end } => format!("bezier {lw} {} {} {}",
+                        point_text(&ctrl.0), point_text(&ctrl.1), point_text(end)),
+                    SegInstr::ArcCircle { large, sweep, radius, end } =>
+                        format!("arc_circle {lw} {radius} {large} {sweep} {}", point_text(end)),
+                    SegInstr::ArcEllipse { large, sweep, radii, rotation, end } =>
+                        format!("arc_ellipse {lw} {} {} {rotation} {large} {sweep} {}",
+                            radii.0, radii.1, point_text(end)),
+                    SegInstr::QuadBezier { ctrl, end } => format!("quadratic_bezier {lw} {} {}",
+                        point_text(ctrl), point_text(end)),
+                    SegInstr::ClosePath => format!("close {lw}"),
+                })?;
+            }   writeln!(writer, "{ind}  )\n{ind})")?;
+        },
+    }   writeln!(writer, "      )\n    )")
+}
+
+fn style_text(style: &Style) -> String {
+    match style {   Style::FlatColor(idx) => format!("(flat {idx})"),
+        Style::LinearGradient { points, cindex } => format!("(linear {} {} {} {})",
+            point_text(&points.0), point_text(&points.1), cindex.0, cindex.1),
+        Style::RadialGradient { points, cindex } => format!("(radial {} {} {} {})",
+            point_text(&points.0), point_text(&points.1), cindex.0, cindex.1),
+    }
+}
+
+#[inline] fn point_text(pt: &Point) -> String { format!("({} {})", pt.x, pt.y) }
+
+fn parse_command(sexp: &Sexp) -> Result<Command> {
+    let (name, args) = match list(sexp)? {
+        [Sexp::Atom(name), args @ ..] => (*name, args),
+        _ => return Err("expect `(command_name ...)'".into()),
+    };
+
+    Ok(match name {
+        "fill_polygon"    => Command::FillPolyg(fill_cmd(name, args, parse_point)?),
+        "fill_rectangles" => Command::FillRects(fill_cmd(name, args, parse_rect)?),
+        "fill_path"       => Command::FillPath (fill_cmd(name, args, parse_segment)?),
+        "draw_lines"      => Command::DrawLines(draw_cmd(name, args, parse_line)?),
+        "draw_line_loop"  => Command::DrawLoop (draw_cmd(name, args, parse_point)?, false),
+        "draw_line_strip" => Command::DrawLoop (draw_cmd(name, args, parse_point)?, true),
+        "draw_line_path"  => Command::DrawPath (draw_cmd(name, args, parse_segment)?),
+        "outline_fill_polygon" => {
+            let (fill, cmd) = outline(name, args, parse_point)?;
+            Command::OutlinePolyg(fill, cmd)
+        }
+        "outline_fill_rectangles" => {
+            let (fill, cmd) = outline(name, args, parse_rect)?;
+            Command::OutlineRects(fill, cmd)
+        }
+        "outline_fill_path" => {
+            let (fill, cmd) = outline(name, args, parse_segment)?;
+            Command::OutlinePath (fill, cmd)
+        }
+        x => return Err(format!("unrecognized command: {x}").into()),
+    })
+}
+
+fn fill_cmd<T>(name: &str, args: &[Sexp],
+    parse_fn: fn(&Sexp) -> Result<T>) -> Result<FillCMD<T>> {
+    let [fill, coll] = args else {
+        return Err(format!("expect `({name} style (...))'").into()) };
+    Ok(FillCMD { fill: parse_style(fill)?, coll: parse_coll(coll, parse_fn)? })
+}
+
+fn draw_cmd<T>(name: &str, args: &[Sexp],
+    parse_fn: fn(&Sexp) -> Result<T>) -> Result<DrawCMD<T>> {
+    let [line, lwidth, coll] = args else {
+        return Err(format!("expect `({name} [fill] line width (...))'").into()) };
+    Ok(DrawCMD { line: parse_style(line)?, lwidth: unit(lwidth)?,
+                 coll: parse_coll(coll, parse_fn)? })
+}
+
+fn outline<T>(name: &str, args: &[Sexp],
+    parse_fn: fn(&Sexp) -> Result<T>) -> Result<(Style, DrawCMD<T>)> {
+    let [fill, args @ ..] = args else {
+        return Err(format!("expect `({name} fill line width (...))'").into()) };
+    let cmd = draw_cmd(name, args, parse_fn)?;
+    if 1 << 6 < cmd.coll.len() { return Err("too many outline elements".into()) }
+    Ok((parse_style(fill)?, cmd))
+}
+
+fn parse_coll<T>(sexp: &Sexp, parse_fn: fn(&Sexp) -> Result<T>) -> Result<Vec<T>> {
+    let coll = list(sexp)?.iter().map(parse_fn).collect::<Result<Vec<_>>>()?;
+    if coll.is_empty() { return Err("empty element list".into()) }  Ok(coll)
+}
+
+fn parse_style(sexp: &Sexp) -> Result<Style> {
+    Ok(match list(sexp)? {
+        [Sexp::Atom("flat"), idx] => Style::FlatColor(atom(idx)?.parse()?),
+        [Sexp::Atom("linear"), p0, p1, c0, c1] => Style::LinearGradient {
+            points: (parse_point(p0)?, parse_point(p1)?),
+            cindex: (atom(c0)?.parse()?, atom(c1)?.parse()?),
+        },
+        [Sexp::Atom("radial"), p0, p1, c0, c1] => Style::RadialGradient {
+            points: (parse_point(p0)?, parse_point(p1)?),
+            cindex: (atom(c0)?.parse()?, atom(c1)?.parse()?),
+        },
+        _ => return Err("expect `(flat c)', `(linear p0 p1 c0 c1)' or `(radial ...)'".into()),
+    })
+}
+
+fn parse_segment(sexp: &Sexp) -> Result<Segment> {
+    let [start, instrs] = list(sexp)? else {
+        return Err("expect `((x y) (instructions...))' segment".into()) };
+    let mut cmds = vec![];
+
+    for instr in list(instrs)? {
+        let (name, lwidth, args) = match list(instr)? {
+            [Sexp::Atom(name), Sexp::Atom("-"), args @ ..] => (*name, None, args),
+            [Sexp::Atom(name), lwidth, args @ ..] => (*name, Some(unit(lwidth)?), args),
+            _ => return Err("expect `(instruction line_width ...)'".into()),
+        };
+
+        let instr = match (name, args) {
+            ("line",  [x, y]) => SegInstr::Line  { end: Point { x: unit(x)?, y: unit(y)? } },
+            ("horiz", [x]) => SegInstr::HLine { x: unit(x)? },
+            ("vert",  [y]) => SegInstr::VLine { y: unit(y)? },
+            ("bezier", [c0, c1, end]) => SegInstr::CubicBezier {
+                ctrl: (parse_point(c0)?, parse_point(c1)?), end: parse_point(end)? },
+            ("quadratic_bezier", [ctrl, end]) => SegInstr::QuadBezier {
+                ctrl: parse_point(ctrl)?, end: parse_point(end)? },
+            ("arc_circle", [radius, large, sweep, end]) => SegInstr::ArcCircle {
+                large: atom(large)?.parse()?, sweep: atom(sweep)?.parse()?,
+                radius: unit(radius)?, end: parse_point(end)? },
+            ("arc_ellipse", [rx, ry, rotation, large, sweep, end]) => SegInstr::ArcEllipse {
+                large: atom(large)?.parse()?, sweep: atom(sweep)?.parse()?,
+                radii: (unit(rx)?, unit(ry)?), rotation: unit(rotation)?,
+                end: parse_point(end)? },
+            ("close", []) => SegInstr::ClosePath,
+            _ => return Err(format!("illegal path instruction: {name}").into()),
+        };  cmds.push(SegmentCommand { instr, lwidth });
+    }
+
+    if cmds.is_empty() { return Err("empty path segment".into()) }
+    Ok(Segment { start: parse_point(start)?, cmds })
+}
+
+fn parse_line(sexp: &Sexp) -> Result<Line> {
+    let [start, end] = list(sexp)? else { return Err("expect `((x y) (x y))' line".into()) };
+    Ok(Line { start: parse_point(start)?, end: parse_point(end)? })
+}
+
+fn parse_rect(sexp: &Sexp) -> Result<Rect> {
+    let [x, y, w, h] = list(sexp)? else { return Err("expect `(x y w h)' rect".into()) };
+    Ok(Rect { x: unit(x)?, y: unit(y)?, w: unit(w)?, h: unit(h)? })
+}
+
+fn parse_point(sexp: &Sexp) -> Result<Point> {
+    let [x, y] = list(sexp)? else { return Err("expect `(x y)' point".into()) };
+    Ok(Point { x: unit(x)?, y: unit(y)? })
+}
+
+#[inline] fn unit(sexp: &Sexp) -> Result<f32> { Ok(atom(sexp)?.parse()?) }
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) { return Err("odd number of hex digits".into()) }
+    (0..hex.len()).step_by(2).map(|i|
+        Ok(u8::from_str_radix(hex.get(i..i + 2).ok_or("invalid hex")?, 16)?)).collect()
+}
+
+enum Sexp<'a> { Atom(&'a str), List(Vec<Sexp<'a>>) }
+
+fn atom<'a>(sexp: &Sexp<'a>) -> Result<&'a str> {
+    if let Sexp::Atom(s) = sexp { Ok(s) } else { Err("expect an atom, got a list".into()) }
+}
+
+fn list<'a, 'b>(sexp: &'b Sexp<'a>) -> Result<&'b [Sexp<'a>]> {
+    if let Sexp::List(v) = sexp { Ok(v) } else { Err("expect a list, got an atom".into()) }
+}
+
+fn parse_sexp<'a>(tokens: &mut Tokens<'a>) -> Result<Sexp<'a>> {
+    match tokens.next().ok_or("unexpected end of text")? {
+        "(" => {    let mut coll = vec![];
+            loop {  match tokens.peek() {
+                    Some(")") => { tokens.next();   break }
+                    Some(_) => coll.push(parse_sexp(tokens)?),
+                    None => return Err("unbalanced parentheses".into()),
+            } }     Ok(Sexp::List(coll))
+        }
+        ")" => Err("unexpected `)'".into()),
+        s => Ok(Sexp::Atom(s)),
+    }
+}
+
+#[derive(Clone)] struct Tokens<'a> { text: &'a str, pos: usize }
+
+impl<'a> Tokens<'a> { fn peek(&self) -> Option<&'a str> { self.clone().next() } }
+
+impl<'a> Iterator for Tokens<'a> { type Item = &'a str;
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = &self.text[self.pos..];
+        let skip = rest.len() - rest.trim_start().len();
+        let rest = &rest[skip..];   self.pos += skip;
+
+        let len = match rest.chars().next()? { '(' | ')' => 1,
+            _ => rest.find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                    .unwrap_or(rest.len()),
+        };  self.pos += len;    Some(&rest[..len])
+    }
+}
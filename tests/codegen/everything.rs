@@ -0,0 +1,636 @@
+// Generated by intvg 0.1.8 from a TinyVG image of 400x768, do not edit.
+
+pub const EVERYTHING_SIZE: (u32, u32) = (400, 768);
+pub const EVERYTHING_COLORS: [[u8; 4]; 6] = [
+    [231, 169, 21, 255], [255, 120, 0, 255], [64, 255, 0, 255], [186, 0, 77, 255],
+    [98, 0, 158, 255], [148, 229, 56, 255],
+];
+
+/// Draw into `pixmap` by `trfm` from image to pixel coordinates.
+pub fn draw_everything(pixmap: &mut tiny_skia::PixmapMut, trfm: tiny_skia::Transform) {
+    use tiny_skia as skia;
+    const C: [[u8; 4]; 6] = EVERYTHING_COLORS;
+    let det = trfm.sx * trfm.sy - trfm.kx * trfm.ky;
+    let sum = trfm.sx * trfm.sx + trfm.kx * trfm.kx + trfm.ky * trfm.ky + trfm.sy * trfm.sy;
+    let smin = ((sum - (sum * sum - 4.0 * det * det).max(0.0).sqrt()) / 2.0).sqrt();
+    let hairline = if 0.0 < smin { 1.0 / smin } else { 0.0 };  // lines of a pixel at least
+    let color = |c: [u8; 4]| skia::Color::from_rgba8(c[0], c[1], c[2], c[3]);
+    let flat  = |c: [u8; 4]| { let mut paint = skia::Paint::default();
+        paint.set_color(color(c));  paint };
+    let gradient = |shader: Option<skia::Shader<'static>>| skia::Paint { shader:
+        shader.unwrap_or(skia::Shader::SolidColor(skia::Color::TRANSPARENT)),
+        ..Default::default() };
+    let stop = |pos, c| skia::GradientStop::new(pos, color(c));
+    let rect = |pb: &mut skia::PathBuilder, x, y, w, h|
+        if let Some(rect) = skia::Rect::from_xywh(x, y, w, h) { pb.push_rect(rect) };
+    let mut stroke = skia::Stroke { line_join: skia::LineJoin::Round,
+        line_cap: skia::LineCap::Round, ..Default::default() };
+    let mut pb = skia::PathBuilder::new();
+    {  // #0
+        let p0 = flat(C[0]);
+        rect(&mut pb, 25.0, 25.0, 100.0, 15.0); rect(&mut pb, 25.0, 45.0, 100.0, 15.0);
+        rect(&mut pb, 25.0, 65.0, 100.0, 15.0);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #1
+        let p0 = flat(C[0]);
+        let p1 = flat(C[3]);
+        rect(&mut pb, 25.0, 105.0, 100.0, 15.0); rect(&mut pb, 25.0, 125.0, 100.0, 15.0);
+        rect(&mut pb, 25.0, 145.0, 100.0, 15.0);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #2
+        let p0 = flat(C[0]);
+        pb.move_to(25.0, 185.0); pb.line_to(125.0, 195.0); pb.move_to(25.0, 195.0);
+        pb.line_to(125.0, 205.0); pb.move_to(25.0, 205.0); pb.line_to(125.0, 215.0);
+        pb.move_to(25.0, 215.0); pb.line_to(125.0, 225.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #3
+        let p0 = flat(C[0]);
+        pb.move_to(25.0, 250.0); pb.line_to(125.0, 260.0); pb.line_to(35.0, 270.0);
+        pb.line_to(125.0, 280.0); pb.line_to(25.0, 290.0); pb.close();
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #4
+        let p0 = flat(C[0]);
+        pb.move_to(25.0, 315.0); pb.line_to(125.0, 325.0); pb.line_to(35.0, 335.0);
+        pb.line_to(125.0, 345.0); pb.line_to(25.0, 355.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #5
+        let p0 = flat(C[0]);
+        pb.move_to(25.0, 380.0); pb.line_to(125.0, 390.0); pb.line_to(35.0, 400.0);
+        pb.line_to(125.0, 410.0); pb.line_to(25.0, 420.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #6
+        let p0 = flat(C[0]);
+        let p1 = flat(C[3]);
+        pb.move_to(25.0, 445.0); pb.line_to(125.0, 455.0); pb.line_to(35.0, 465.0);
+        pb.line_to(125.0, 475.0); pb.line_to(25.0, 485.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #7
+        let p0 = flat(C[0]);
+        pb.move_to(25.0, 510.0); pb.line_to(35.0, 510.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(35.0, 510.0); pb.line_to(35.0, 520.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(35.0, 520.0); pb.line_to(45.0, 520.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(45.0, 520.0); pb.line_to(125.0, 510.0);
+        stroke.width = hairline.max(1.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(125.0, 510.0); pb.cubic_to(100.0, 530.0, 115.0, 560.0, 100.0, 560.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(100.0, 560.0); pb.quad_to(75.0, 560.0, 75.0, 535.0);
+        stroke.width = hairline.max(4.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(75.0, 535.0);
+        pb.cubic_to(66.139114, 533.68396, 57.196228, 537.2611, 50.0, 545.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(50.0, 545.0);
+        pb.cubic_to(46.1742, 549.94586, 39.532513, 551.75507, 33.726883, 549.4328);
+        pb.cubic_to(27.921253, 547.11053, 24.359476, 541.22, 25.0, 535.0);
+        stroke.width = hairline.max(1.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(25.0, 535.0); pb.line_to(25.0, 510.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #8
+        let p0 = flat(C[0]);
+        pb.move_to(25.0, 585.0); pb.line_to(35.0, 585.0); pb.line_to(35.0, 595.0);
+        pb.line_to(45.0, 595.0); pb.line_to(125.0, 585.0);
+        pb.cubic_to(100.0, 605.0, 115.0, 635.0, 100.0, 635.0);
+        pb.quad_to(75.0, 635.0, 75.0, 610.0);
+        pb.cubic_to(66.139114, 608.68396, 57.196228, 612.2611, 50.0, 620.0);
+        pb.cubic_to(46.1742, 624.94586, 39.532513, 626.75507, 33.726883, 624.4328);
+        pb.cubic_to(27.921253, 622.11053, 24.359476, 616.22, 25.0, 610.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #9
+        let p0 = flat(C[0]);
+        let p1 = flat(C[3]);
+        pb.move_to(25.0, 660.0); pb.line_to(35.0, 660.0); pb.line_to(35.0, 670.0);
+        pb.line_to(45.0, 670.0); pb.line_to(125.0, 660.0);
+        pb.cubic_to(100.0, 680.0, 115.0, 710.0, 100.0, 710.0);
+        pb.quad_to(75.0, 710.0, 75.0, 685.0);
+        pb.cubic_to(66.139114, 683.68396, 57.196228, 687.2611, 50.0, 695.0);
+        pb.cubic_to(46.1742, 699.94586, 39.532513, 701.75507, 33.726883, 699.4328);
+        pb.cubic_to(27.921253, 697.11053, 24.359476, 691.22, 25.0, 685.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #10
+        let p0 = gradient(skia::LinearGradient::new((150.0, 25.0).into(),
+            (250.0, 75.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        rect(&mut pb, 150.0, 25.0, 100.0, 15.0); rect(&mut pb, 150.0, 45.0, 100.0, 15.0);
+        rect(&mut pb, 150.0, 65.0, 100.0, 15.0);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #11
+        let p0 = gradient(skia::LinearGradient::new((150.0, 105.0).into(),
+            (250.0, 155.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        let p1 = flat(C[3]);
+        rect(&mut pb, 150.0, 105.0, 100.0, 15.0); rect(&mut pb, 150.0, 125.0, 100.0, 15.0);
+        rect(&mut pb, 150.0, 145.0, 100.0, 15.0);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #12
+        let p0 = gradient(skia::LinearGradient::new((150.0, 185.0).into(),
+            (250.0, 235.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(150.0, 185.0); pb.line_to(250.0, 195.0); pb.move_to(150.0, 195.0);
+        pb.line_to(250.0, 205.0); pb.move_to(150.0, 205.0); pb.line_to(250.0, 215.0);
+        pb.move_to(150.0, 215.0); pb.line_to(250.0, 225.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #13
+        let p0 = gradient(skia::LinearGradient::new((150.0, 250.0).into(),
+            (250.0, 300.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(150.0, 250.0); pb.line_to(250.0, 260.0); pb.line_to(160.0, 270.0);
+        pb.line_to(250.0, 280.0); pb.line_to(150.0, 290.0); pb.close();
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #14
+        let p0 = gradient(skia::LinearGradient::new((150.0, 315.0).into(),
+            (250.0, 365.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(150.0, 315.0); pb.line_to(250.0, 325.0); pb.line_to(160.0, 335.0);
+        pb.line_to(250.0, 345.0); pb.line_to(150.0, 355.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #15
+        let p0 = gradient(skia::LinearGradient::new((150.0, 380.0).into(),
+            (250.0, 430.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(150.0, 380.0); pb.line_to(250.0, 390.0); pb.line_to(160.0, 400.0);
+        pb.line_to(250.0, 410.0); pb.line_to(150.0, 420.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #16
+        let p0 = gradient(skia::LinearGradient::new((150.0, 445.0).into(),
+            (250.0, 495.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        let p1 = flat(C[3]);
+        pb.move_to(150.0, 445.0); pb.line_to(250.0, 455.0); pb.line_to(160.0, 465.0);
+        pb.line_to(250.0, 475.0); pb.line_to(150.0, 485.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #17
+        let p0 = gradient(skia::LinearGradient::new((150.0, 510.0).into(),
+            (250.0, 560.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(150.0, 510.0); pb.line_to(160.0, 510.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(160.0, 510.0); pb.line_to(160.0, 520.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(160.0, 520.0); pb.line_to(170.0, 520.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(170.0, 520.0); pb.line_to(250.0, 510.0);
+        stroke.width = hairline.max(1.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(250.0, 510.0); pb.cubic_to(225.0, 530.0, 240.0, 560.0, 225.0, 560.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(225.0, 560.0); pb.quad_to(200.0, 560.0, 200.0, 535.0);
+        stroke.width = hairline.max(4.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(200.0, 535.0);
+        pb.cubic_to(191.13911, 533.68396, 182.19623, 537.2611, 175.0, 545.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(175.0, 545.0);
+        pb.cubic_to(171.1742, 549.94586, 164.53252, 551.75507, 158.72688, 549.4328);
+        pb.cubic_to(152.92125, 547.11053, 149.35948, 541.22, 150.0, 535.0);
+        stroke.width = hairline.max(1.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(150.0, 535.0); pb.line_to(150.0, 510.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #18
+        let p0 = gradient(skia::LinearGradient::new((150.0, 585.0).into(),
+            (250.0, 635.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(150.0, 585.0); pb.line_to(160.0, 585.0); pb.line_to(160.0, 595.0);
+        pb.line_to(170.0, 595.0); pb.line_to(250.0, 585.0);
+        pb.cubic_to(225.0, 605.0, 240.0, 635.0, 225.0, 635.0);
+        pb.quad_to(200.0, 635.0, 200.0, 610.0);
+        pb.cubic_to(191.13911, 608.68396, 182.19623, 612.2611, 175.0, 620.0);
+        pb.cubic_to(171.1742, 624.94586, 164.53252, 626.75507, 158.72688, 624.4328);
+        pb.cubic_to(152.92125, 622.11053, 149.35948, 616.22, 150.0, 610.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #19
+        let p0 = gradient(skia::LinearGradient::new((150.0, 660.0).into(),
+            (250.0, 710.0).into(), vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        let p1 = flat(C[3]);
+        pb.move_to(150.0, 660.0); pb.line_to(160.0, 660.0); pb.line_to(160.0, 670.0);
+        pb.line_to(170.0, 670.0); pb.line_to(250.0, 660.0);
+        pb.cubic_to(225.0, 680.0, 240.0, 710.0, 225.0, 710.0);
+        pb.quad_to(200.0, 710.0, 200.0, 685.0);
+        pb.cubic_to(191.13911, 683.68396, 182.19623, 687.2611, 175.0, 695.0);
+        pb.cubic_to(171.1742, 699.94586, 164.53252, 701.75507, 158.72688, 699.4328);
+        pb.cubic_to(152.92125, 697.11053, 149.35948, 691.22, 150.0, 685.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #20
+        let p0 = gradient(skia::RadialGradient::new((325.0, 50.0).into(), 0.0,
+            (325.0, 50.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        rect(&mut pb, 275.0, 25.0, 100.0, 15.0); rect(&mut pb, 275.0, 45.0, 100.0, 15.0);
+        rect(&mut pb, 275.0, 65.0, 100.0, 15.0);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #21
+        let p0 = gradient(skia::RadialGradient::new((325.0, 130.0).into(), 0.0,
+            (325.0, 130.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        let p1 = flat(C[3]);
+        rect(&mut pb, 275.0, 105.0, 100.0, 15.0); rect(&mut pb, 275.0, 125.0, 100.0, 15.0);
+        rect(&mut pb, 275.0, 145.0, 100.0, 15.0);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #22
+        let p0 = gradient(skia::RadialGradient::new((325.0, 210.0).into(), 0.0,
+            (325.0, 210.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(275.0, 185.0); pb.line_to(375.0, 195.0); pb.move_to(275.0, 195.0);
+        pb.line_to(375.0, 205.0); pb.move_to(275.0, 205.0); pb.line_to(375.0, 215.0);
+        pb.move_to(275.0, 215.0); pb.line_to(375.0, 225.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #23
+        let p0 = gradient(skia::RadialGradient::new((325.0, 275.0).into(), 0.0,
+            (325.0, 275.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(275.0, 250.0); pb.line_to(375.0, 260.0); pb.line_to(285.0, 270.0);
+        pb.line_to(375.0, 280.0); pb.line_to(275.0, 290.0); pb.close();
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #24
+        let p0 = gradient(skia::RadialGradient::new((325.0, 340.0).into(), 0.0,
+            (325.0, 340.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(275.0, 315.0); pb.line_to(375.0, 325.0); pb.line_to(285.0, 335.0);
+        pb.line_to(375.0, 345.0); pb.line_to(275.0, 355.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #25
+        let p0 = gradient(skia::RadialGradient::new((325.0, 405.0).into(), 0.0,
+            (325.0, 405.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(275.0, 380.0); pb.line_to(375.0, 390.0); pb.line_to(285.0, 400.0);
+        pb.line_to(375.0, 410.0); pb.line_to(275.0, 420.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #26
+        let p0 = gradient(skia::RadialGradient::new((325.0, 470.0).into(), 0.0,
+            (325.0, 470.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        let p1 = flat(C[3]);
+        pb.move_to(275.0, 445.0); pb.line_to(375.0, 455.0); pb.line_to(285.0, 465.0);
+        pb.line_to(375.0, 475.0); pb.line_to(275.0, 485.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #27
+        let p0 = gradient(skia::RadialGradient::new((325.0, 535.0).into(), 0.0,
+            (325.0, 535.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(275.0, 510.0); pb.line_to(285.0, 510.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(285.0, 510.0); pb.line_to(285.0, 520.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(285.0, 520.0); pb.line_to(295.0, 520.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(295.0, 520.0); pb.line_to(375.0, 510.0);
+        stroke.width = hairline.max(1.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(375.0, 510.0); pb.cubic_to(350.0, 530.0, 365.0, 560.0, 350.0, 560.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(350.0, 560.0); pb.quad_to(325.0, 560.0, 325.0, 535.0);
+        stroke.width = hairline.max(4.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(325.0, 535.0);
+        pb.cubic_to(316.13913, 533.68396, 307.19623, 537.2611, 300.0, 545.0);
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(300.0, 545.0);
+        pb.cubic_to(296.1742, 549.94586, 289.5325, 551.75507, 283.7269, 549.4328);
+        pb.cubic_to(277.92126, 547.11053, 274.35947, 541.22, 275.0, 535.0);
+        stroke.width = hairline.max(1.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+        pb.move_to(275.0, 535.0); pb.line_to(275.0, 510.0);
+        stroke.width = hairline.max(3.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p0, &stroke, trfm, None) }
+        pb.clear();
+    }
+    {  // #28
+        let p0 = gradient(skia::RadialGradient::new((325.0, 610.0).into(), 0.0,
+            (325.0, 610.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        pb.move_to(275.0, 585.0); pb.line_to(285.0, 585.0); pb.line_to(285.0, 595.0);
+        pb.line_to(295.0, 595.0); pb.line_to(375.0, 585.0);
+        pb.cubic_to(350.0, 605.0, 365.0, 635.0, 350.0, 635.0);
+        pb.quad_to(325.0, 635.0, 325.0, 610.0);
+        pb.cubic_to(316.13913, 608.68396, 307.19623, 612.2611, 300.0, 620.0);
+        pb.cubic_to(296.1742, 624.94586, 289.5325, 626.75507, 283.7269, 624.4328);
+        pb.cubic_to(277.92126, 622.11053, 274.35947, 616.22, 275.0, 610.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        pb.clear();
+    }
+    {  // #29
+        let p0 = gradient(skia::RadialGradient::new((325.0, 685.0).into(), 0.0,
+            (325.0, 685.0).into(), 55.9017, vec![
+            stop(0.0, [255, 120, 0, 255]), stop(0.125, [241, 146, 0, 255]),
+            stop(0.25, [225, 167, 0, 255]), stop(0.375, [209, 185, 0, 255]),
+            stop(0.5, [190, 201, 0, 255]), stop(0.625, [169, 216, 0, 255]),
+            stop(0.75, [144, 230, 0, 255]), stop(0.8125, [130, 237, 0, 255]),
+            stop(0.875, [113, 243, 0, 255]), stop(0.9375, [92, 249, 0, 255]),
+            stop(0.96875, [80, 252, 0, 255]), stop(1.0, [64, 255, 0, 255]),
+            ], skia::SpreadMode::Pad, skia::Transform::identity()));
+        let p1 = flat(C[3]);
+        pb.move_to(275.0, 660.0); pb.line_to(285.0, 660.0); pb.line_to(285.0, 670.0);
+        pb.line_to(295.0, 670.0); pb.line_to(375.0, 660.0);
+        pb.cubic_to(350.0, 680.0, 365.0, 710.0, 350.0, 710.0);
+        pb.quad_to(325.0, 710.0, 325.0, 685.0);
+        pb.cubic_to(316.13913, 683.68396, 307.19623, 687.2611, 300.0, 695.0);
+        pb.cubic_to(296.1742, 699.94586, 289.5325, 701.75507, 283.7269, 699.4328);
+        pb.cubic_to(277.92126, 697.11053, 274.35947, 691.22, 275.0, 685.0); pb.close();
+        if let Some(path) = pb.clone().finish() {
+            pixmap.fill_path(&path, &p0, skia::FillRule::Winding, trfm, None) }
+        stroke.width = hairline.max(2.5);
+        if let Some(path) = pb.clone().finish() {
+            pixmap.stroke_path(&path, &p1, &stroke, trfm, None) }
+        pb.clear();
+    }
+}
@@ -1,10 +1,14 @@
 
+/// Directory the tests write images into, created on demand since tests run in any order.
+fn images_dir() -> std::io::Result<std::path::PathBuf> {
+    let idir = std::path::PathBuf::from("target/images");
+    std::fs::create_dir_all(&idir)?;    Ok(idir)
+}
+
 #[test] fn tinyvg() -> Result<(), Box<dyn std::error::Error>> {
     use intvg::{tinyvg::TVGImage, render::Render, convert::Convert};
     use std::{fs::{self, File}, io::{BufReader, BufWriter}, path::PathBuf};
-    let idir = PathBuf::from("target/images");
-    let tigr = PathBuf::from("data/tiger.svg");
-    fs::create_dir_all(&idir)?;
+    let (idir, tigr) = (images_dir()?, PathBuf::from("data/tiger.svg"));
 
     /* let bexe = std::env::var("CARGO_BIN_EXE_intvg")?;
     for fname in ["foo.tvg", "foo.png"] {
@@ -44,3 +48,633 @@
     }   Ok(())
 }
 
+
+#[test] fn tvgt_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGBuf, tvgt::TextFormat};
+    use std::{fs, io::Cursor};
+
+    for entry in fs::read_dir("data")? { let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "tvg") { continue }
+        let data = fs::read(&path)?;
+
+        let tvg = TVGBuf::load_data(&mut Cursor::new(&data))?;
+        let mut text = vec![];  tvg.save_text(&mut text)?;
+        let tvgt = TVGBuf::load_text(std::str::from_utf8(&text)?)?;
+
+        let (mut bin0, mut bin1) = (Cursor::new(vec![]), Cursor::new(vec![]));
+        tvg .save_data(&mut bin0)?;     tvgt.save_data(&mut bin1)?;
+        assert!(bin0.get_ref() == bin1.get_ref(), "lossy text round trip: {}", path.display());
+
+        let mut text1 = vec![]; tvgt.save_text(&mut text1)?;    assert_eq!(text, text1);
+    }   Ok(())
+}
+
+#[cfg(feature = "serde")] #[test] fn json_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::tinyvg::TVGBuf;
+    use std::{fs, io::Cursor};
+
+    for entry in fs::read_dir("data")? { let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "tvg") { continue }
+        let data = fs::read(&path)?;
+
+        let tvg = TVGBuf::load_data(&mut Cursor::new(&data))?;
+        let json = tvg.to_json()?;  let tvgj = TVGBuf::from_json(&json)?;
+
+        let (mut bin0, mut bin1) = (Cursor::new(vec![]), Cursor::new(vec![]));
+        tvg .save_data(&mut bin0)?;     tvgj.save_data(&mut bin1)?;
+        assert!(bin0.get_ref() == bin1.get_ref(), "lossy JSON round trip: {}", path.display());
+        assert_eq!(json, tvgj.to_json()?);
+    }   Ok(())
+}
+
+#[test] fn svg_ids_as_named_ranges() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, convert::Convert};
+    let tvg = TVGImage::from_usvg(&std::fs::read("data/coordinates.svg")?)?;
+
+    let names = tvg.named_ranges();
+    let range = tvg.lookup_name("path1601").expect("missing SVG id");
+    assert!(!names.is_empty() && range.len() == 1 && range.end <= tvg.commands.len());
+    assert!(names.iter().any(|nr| nr.depth > 0 && nr.range.contains(&range.start)));
+    assert!(tvg.html_image_map("coords", 1.0).contains("href=\"#path1601\""));
+    Ok(())
+}
+
+#[test] fn svg_layers_toggle() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, convert::Convert, render::Render};
+    let tvg = TVGImage::from_usvg(br#"<svg xmlns="http://www.w3.org/2000/svg"
+        xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="8" height="8">
+        <g id="layer1" inkscape:groupmode="layer" inkscape:label="Back">
+            <rect width="8" height="8" fill="red"/></g>
+        <g id="layer2" inkscape:groupmode="layer" inkscape:label="Front">
+            <rect x="2" y="2" width="4" height="4" fill="blue"/></g></svg>"#)?;
+
+    let layers = tvg.layers();
+    assert_eq!(layers.iter().map(|layer| layer.name.as_str()).collect::<Vec<_>>(),
+        ["Back", "Front"]);
+
+    let (all, back) = (tvg.render(1.0)?, tvg.render_layers(1.0, Some(&["Back"]))?);
+    let front = tvg.render_layers(1.0, Some(&["Front"]))?;
+    assert_eq!(all .pixel(4, 4).map(|px| (px.red(), px.blue())), Some((0, 255)));
+    assert_eq!(back.pixel(4, 4).map(|px| (px.red(), px.blue())), Some((255, 0)));
+    assert_eq!(front.pixel(0, 0).map(|px| px.alpha()), Some(0));
+    Ok(())
+}
+
+#[test] fn render_options_fit_and_background() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::*, convert::Convert, render::*};
+    let tvg = TVGImage::from_usvg(br#"<svg xmlns="http://www.w3.org/2000/svg"
+        width="8" height="4"><rect width="8" height="4" fill="blue"/></svg>"#)?;
+    let white = RGBA8888 { r: 255, g: 255, b: 255, a: 255 };
+
+    let opts = RenderOptions { size: Some((24, 24)), background: Some(white),
+        ..Default::default() };     let img = tvg.render_with(&opts)?;
+    assert_eq!((img.width(), img.height()), (24, 24));
+    let blue = |img: &tiny_skia::Pixmap, x, y| img.pixel(x, y).unwrap().blue() == 255 &&
+        img.pixel(x, y).unwrap().red() == 0;
+    assert!(!blue(&img, 12, 2) && blue(&img, 12, 12) && !blue(&img, 12, 21));
+
+    let img = tvg.render_with(&RenderOptions { align: (Align::Mid, Align::Min), ..opts.clone() })?;
+    assert!(blue(&img, 12, 2) && !blue(&img, 12, 14));
+    let img = tvg.render_with(&RenderOptions { fit: Fit::Cover, ..opts.clone() })?;
+    assert!(blue(&img, 0, 0) && blue(&img, 23, 23));
+    let img = tvg.render_with(&RenderOptions { fit: Fit::Stretch, ..opts.clone() })?;
+    assert!(blue(&img, 0, 0) && blue(&img, 23, 23));
+
+    let img = tvg.render_with(&RenderOptions { scale: 2.0, transform:
+        tiny_skia::Transform::from_translate(0.0, 8.0), ..Default::default() })?;
+    assert_eq!((img.width(), img.height()), (16, 8));
+    assert_eq!(img.pixel(4, 4).unwrap().alpha(), 0);
+    Ok(())
+}
+
+#[test] fn render_into_with_mask_and_opacity() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, convert::Convert, render::Render};
+    use tiny_skia::{Pixmap, Mask, PathBuilder, Rect, Transform, FillRule, Color};
+    let tvg = TVGImage::from_usvg(br#"<svg xmlns="http://www.w3.org/2000/svg"
+        width="8" height="4"><rect width="8" height="4" fill="blue"/></svg>"#)?;
+
+    let mut canvas = Pixmap::new(16, 16).unwrap();  canvas.fill(Color::WHITE);
+    let mut mask = Mask::new(16, 16).unwrap();
+    mask.fill_path(&PathBuilder::from_rect(Rect::from_xywh(0.0, 0.0, 8.0, 16.0).unwrap()),
+        FillRule::Winding, false, Transform::identity());
+
+    let trfm = Transform::from_translate(4.0, 4.0);
+    tvg.render_into(&mut canvas.as_mut(), trfm, Some(&mask), 0.5)?;
+    let px = canvas.pixel(5, 5).unwrap();
+    assert!(px.blue() == 255 && (120..136).contains(&px.red()));
+    assert_eq!(canvas.pixel(10, 5).unwrap().red(), 255);    // masked out
+    assert_eq!(canvas.pixel(5, 10).unwrap().red(), 255);    // outside of image
+
+    tvg.render_into(&mut canvas.as_mut(), trfm.post_translate(0.0, 6.0), None, 1.0)?;
+    assert_eq!(canvas.pixel(10, 11).unwrap().red(), 0);
+    Ok(())
+}
+
+#[test] fn linear_light_gradients() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, convert::Convert, render::*};
+    let tvg = TVGImage::from_usvg(&std::fs::read("data/gradients.svg")?)?;
+    let refer = tiny_skia::Pixmap::load_png("data/gradients.png")?;
+    let diff = |img: &tiny_skia::Pixmap| (60..100).chain(110..150).map(|x| {
+        let (a, b) = (img.pixel(x, 30).unwrap(), refer.pixel(x, 30).unwrap());
+        (a.green() as i32 - b.green() as i32).abs()
+    }).max().unwrap();
+
+    let linear = diff(&tvg.render(1.0)?);
+    let legacy = diff(&tvg.render_with(&RenderOptions {
+        srgb_gradient: true, ..Default::default() })?);
+    assert!(linear <= 2 && 32 < legacy);
+    Ok(())
+}
+
+#[test] fn hairline_minimum_width() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::Render, tvgt::TextFormat};
+    let tvg = TVGImage::load_text("(tvg 1 (16 16 1/1 u8888 default) ((0 0 0)) (
+        (draw_lines (flat 0) 0.1 ( ((0 4.5) (16 4.5)) ))
+        (draw_line_path (flat 0) 4 ( ((0 12.5) ( (line 0.05 16 12.5) )) )) ))")?;
+
+    let img = tvg.render(1.0)?;
+    for y in [4, 12] { assert!(250 <= img.pixel(8, y).unwrap().alpha()); }
+    for y in [3, 5, 11, 13] { assert!(img.pixel(8, y).unwrap().alpha() < 8); }
+
+    let img = tvg.render(0.5)?;     // still one pixel wide (in two rows) at half size
+    assert!((200..=256).contains(&(img.pixel(4, 1).unwrap().alpha() as u32 +
+        img.pixel(4, 2).unwrap().alpha() as u32)));
+    Ok(())
+}
+
+#[test] fn anti_aliasing_quality() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::{TVGImage, RGBA8888}, render::*, tvgt::TextFormat};
+    use std::{fs::File, io::BufReader};
+    let mean_diff = |a: &tiny_skia::Pixmap, b: &tiny_skia::Pixmap| a.data().iter()
+        .zip(b.data()).map(|(a, b)| (*a as i32 - *b as i32).abs()).sum::<i32>() as f32 /
+            a.data().len() as f32;
+
+    for (stem, tolerance) in [("shield-16", 2.0), ("everything", 4.0)] {
+        let tvg = TVGImage::load_data(&mut BufReader::new(File::open(
+            format!("data/{stem}.tvg"))?))?;
+        let refer = tiny_skia::Pixmap::load_png(format!("data/{stem}.png"))?;
+        for filter in [Filter::Box, Filter::Lanczos] {
+            let img = tvg.render_with(&RenderOptions { supersample: 4, filter,
+                ..Default::default() })?;
+            assert!(mean_diff(&img, &refer) < tolerance);
+        }
+
+        let img = tvg.render_with(&RenderOptions { anti_alias: false, ..Default::default() })?;
+        assert!(img.pixels().iter().all(|px| px.alpha() == 0 || px.alpha() == 255));
+    }
+
+    let tvg = TVGImage::load_text("(tvg 1 (4 4 1/1 u8888 default) ((0 0 0)) (
+        (fill_rectangles (flat 0) ( (0 0 2.5 4) )) ))")?;
+    let opts = RenderOptions { supersample: 4,
+        background: Some(RGBA8888 { r: 255, g: 255, b: 255, a: 255 }), ..Default::default() };
+    let img = tvg.render_with(&opts)?;      // half covered edge pixel
+    assert!((126..=130).contains(&img.pixel(2, 1).unwrap().red()));
+    let img = tvg.render_with(&RenderOptions { gamma_correct: true, ..opts })?;
+    assert!((184..=188).contains(&img.pixel(2, 1).unwrap().red()));
+    Ok(())
+}
+
+#[test] fn tiled_region_rendering() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::*};
+    use std::{fs::File, io::BufReader};
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/tiger.tvg")?))?;
+    let opts = RenderOptions { scale: 2.0, ..Default::default() };
+
+    let full = tvg.render_with(&opts)?;
+    let mut canvas = tiny_skia::Pixmap::new(full.width(), full.height()).unwrap();
+    for opts in opts.tiles(tvg.header.width, tvg.header.height, (128, 96)) {
+        let (rect, tile) = (opts.region.unwrap(), tvg.render_with(&opts)?);
+        assert!(tile.width() <= 128 && tile.height() <= 96);
+        canvas.draw_pixmap(rect.x(), rect.y(), tile.as_ref(), &Default::default(),
+            tiny_skia::Transform::identity(), None);
+    }
+
+    assert!(canvas.data() == full.data());
+
+    let deep = tvg.render_with(&RenderOptions { scale: 8.0,
+        region: tiny_skia::IntRect::from_xywh(1600, 1600, 64, 64), ..Default::default() })?;
+    assert!(deep.width() == 64 && deep.pixels().iter().any(|px| px.alpha() == 255));
+    Ok(())
+}
+
+#[test] fn parallel_band_rendering() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::*};
+    use std::{fs::File, io::BufReader};
+    for (stem, supersample) in [("tiger", 1), ("everything", 2)] {
+        let tvg = TVGImage::load_data(&mut BufReader::new(File::open(
+            format!("data/{stem}.tvg"))?))?;
+        let opts = RenderOptions { scale: 1.5, supersample, ..Default::default() };
+        let single = tvg.render_with(&opts)?;
+
+        for threads in [0, 2, 3, 8] {
+            let img = tvg.render_with(&RenderOptions { threads, ..opts.clone() })?;
+            assert!(img.data() == single.data(), "{stem} differs on {threads} threads");
+        }
+    }   Ok(())
+}
+
+#[test] fn prepared_scene_rerendering() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::*};
+    use std::{fs::File, io::BufReader};
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/tiger.tvg")?))?;
+    let scene = PreparedScene::new(&tvg, &RenderOptions::default())?;
+
+    for (scale, size) in [(0.5, None), (1.0, None), (1.0, Some((300, 200)))] {
+        let opts = RenderOptions { scale, size, ..Default::default() };
+        assert!(scene.render_with(&opts)?.data() == tvg.render_with(&opts)?.data());
+    }   Ok(())
+}
+
+#[test] fn step_through_build_up() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::{TVGImage, RGBA8888}, render::*};
+    use std::{fs::File, io::BufReader};
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/tiger.tvg")?))?;
+    let opts = RenderOptions { scale: 0.5, ..Default::default() };
+
+    let (len, half) = (tvg.commands.len(), tvg.commands.len() / 2);
+    let img = tvg.render_with(&RenderOptions { commands: Some(0..half), ..opts.clone() })?;
+    assert!(img.data() == tvg.render_filtered(&opts, &|i, _| i < half)?.data());
+    assert!(img.data() != tvg.render_with(&opts)?.data());
+    assert!(tvg.render_with(&RenderOptions { commands: Some(len..len), ..opts.clone() })?
+        .pixels().iter().all(|pixel| pixel.alpha() == 0));
+
+    let highlight = RGBA8888 { r: 255, g: 0, b: 255, a: 255 };
+    let scene = PreparedScene::new(&tvg, &opts)?;
+    let frames = scene.build_up(&opts, highlight)?;
+    assert!(0 < frames.len() && frames.len() <= len);
+
+    let path = images_dir()?.join("tiger.apng");
+    save_apng(frames, &path, 40)?;
+    let reader = png::Decoder::new(BufReader::new(File::open(&path)?)).read_info()?;
+    let actl = reader.info().animation_control.ok_or("not an APNG")?;
+    assert!(actl.num_frames as usize == scene.build_up(&opts, highlight)?.len());
+    Ok(())
+}
+
+#[test] fn debug_overlay_marks() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::*};
+    use std::{fs::File, io::BufReader};
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/everything.tvg")?))?;
+    let opts = RenderOptions { debug: true, supersample: 2, ..Default::default() };
+    let (img, plain) = (tvg.render_with(&opts)?,
+        tvg.render_with(&RenderOptions { debug: false, ..opts.clone() })?);
+    assert!(img.data() != plain.data());
+
+    let count = |r, g, b| img.pixels().iter().filter(|pixel| pixel.alpha() == 255 &&
+        (pixel.red(), pixel.green(), pixel.blue()) == (r, g, b)).count();
+    assert!(count(255, 0, 255) >= tvg.commands.len() * 2 * 7 * 2);   // labels
+    assert!(0 < count(255, 255, 255));  // digits
+    assert!(0 < count(255, 128, 0));    // control points
+    img.save_png(images_dir()?.join("everything_debug.png"))?;
+    Ok(())
+}
+
+#[test] fn render_profiling() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::*};
+    use std::{fs::File, io::BufReader};
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/tiger.tvg")?))?;
+    let opts = RenderOptions { scale: 0.5, ..Default::default() };
+    let (img, profile) = tvg.profile(&opts)?;
+    assert!(img.data() == tvg.render_with(&opts)?.data());
+
+    assert!(profile.commands.len() == tvg.commands.len());
+    assert!(profile.commands.iter().map(|cost| cost.pixels).sum::<u64>() ==
+            profile.overdraw.iter().map(|count| *count as u64).sum::<u64>());
+    assert!(profile.commands.iter().all(|cost| 0 < cost.verbs));
+    assert!(1 < *profile.overdraw.iter().max().unwrap());
+    assert!(profile.slowest()[0].time >= profile.slowest()[1].time);
+    assert!(profile.to_string().starts_with("tiny-skia:"));
+
+    let heatmap = profile.heatmap().ok_or("no heatmap")?;
+    assert!((heatmap.width(), heatmap.height()) == (img.width(), img.height()));
+    heatmap.save_png(images_dir()?.join("tiger_overdraw.png"))?;
+    Ok(())
+}
+
+#[test] fn canvas_backend_trait() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::*, canvas::Canvas, tvgt::TextFormat};
+    use std::{io, convert::Infallible};
+
+    #[derive(Default)] struct Strokes { verbs: usize, widths: Vec<(usize, f32)> }
+    impl Canvas for Strokes {
+        type Paint = ();    type Error = Infallible;
+        fn paint<R: io::Read, W: io::Write>(&mut self, _: &TinyVG<R, W>,
+            _: &Style) -> Result<(), Infallible> { Ok(()) }
+
+        fn move_to (&mut self, _: Point) { self.verbs += 1 }
+        fn line_to (&mut self, _: Point) { self.verbs += 1 }
+        fn quad_to (&mut self, _: Point, _: Point) { self.verbs += 1 }
+        fn cubic_to(&mut self, _: (Point, Point), _: Point) { self.verbs += 1 }
+        fn arc_to(&mut self, _: Point, _: (f32, f32), _: f32,
+            _: bool, _: bool, _: Point) -> Result<(), Infallible> { self.verbs += 1; Ok(()) }
+        fn close(&mut self) { self.verbs += 1 }
+        fn rect (&mut self, _: &Rect) -> Result<(), Infallible> { self.verbs += 1; Ok(()) }
+
+        fn fill  (&mut self, _: &()) -> Result<(), Infallible> { Ok(()) }
+        fn stroke(&mut self, _: &(), width: f32) -> Result<(), Infallible> {
+            self.widths.push((self.verbs, width));  Ok(())
+        }
+        fn clear (&mut self) { self.verbs = 0 }
+    }
+
+    let tvg = TVGImage::load_text("(tvg 1 (16 16 1/1 u8888 default) ((0 0 0)) (
+        (draw_line_path (flat 0) 1 ( ((0 0) ( (line - 8 0) (line 2 8 8) (horiz - 0)
+            (line 3 0 16) (close -) )) )) ))")?;
+    let mut canvas = Strokes::default();
+    for cmd in &tvg.commands { canvas.draw_command(&tvg, cmd)? }
+    assert!(canvas.widths == [(2, 1.0), (3, 2.0), (3, 3.0)]);    // split where widths change
+    assert!(canvas.verbs == 0);
+
+    let tvg = TVGImage::load_text("(tvg 1 (16 16 1/1 u8888 default) ((0 0 0)) (
+        (outline_fill_path (flat 0) (flat 0) 1 ( ((0 0) ( (line - 8 0) (line 2 8 8) ))
+            ((0 16) ( (line - 8 16) (line - 16 16) )) )) ))")?;
+    let mut canvas = Strokes::default();    // a change in any segment splits all
+    for cmd in &tvg.commands { canvas.draw_command(&tvg, cmd)? }
+    assert!(canvas.widths == [(2, 1.0), (2, 2.0), (3, 2.0)]);
+    Ok(())
+}
+
+#[test] fn grays_rasterizer_backend() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::*};
+    use std::{fs::File, io::BufReader};
+    for name in ["tiger", "everything"] {
+        let tvg = TVGImage::load_data(&mut BufReader::new(
+            File::open(format!("data/{name}.tvg"))?))?;
+        let (img, skia) = (intvg::render_grays::Render::render(&tvg, 1.0)?, tvg.render(1.0)?);
+        assert!((img.width(), img.height()) == (skia.width(), skia.height()));
+
+        let diff = img.data().iter().zip(skia.data()).map(|(a, b)|
+            a.abs_diff(*b) as u64).sum::<u64>() as f32 / img.data().len() as f32;
+        assert!(diff < 2.0, "{name}: mean difference {diff} from tiny-skia");
+        img.save_png(images_dir()?.join(format!("{name}_grays.png")))?;
+    }
+
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/shield.tvg")?))?;
+    let opts = RenderOptions { scale: 2.0, anti_alias: false, ..Default::default() };
+    let img = intvg::render_grays::Render::render_with(&tvg, &opts)?;
+    assert!(img.pixels().iter().all(|pixel| matches!(pixel.alpha(), 0 | 255)));
+    let opts = RenderOptions { supersample: 2, ..Default::default() };
+    assert!(intvg::render_grays::Render::render_with(&tvg, &opts)?.width() == 24);
+    Ok(())
+}
+
+#[test] fn reference_images() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, convert::Convert, render::RenderOptions, compare::*};
+    use std::{fs::{self, File}, io::BufReader};
+    // XXX: the references draw hairlines thinner (tiger)
+    for (name, psnr, ssim) in [("tiger.tvg", 22.0, 0.94), ("everything.tvg", 31.5, 0.993),
+        ("everything-32.tvg", 31.5, 0.993), ("shield-8.tvg", 31.0, 0.995),
+        ("shield-16.tvg", 31.0, 0.995), ("shield-32.tvg", 31.0, 0.995),
+        ("gradients.svg", 51.0, 0.999)] {
+        let path = std::path::Path::new("data").join(name);
+        let tvg = if name.ends_with(".tvg") {
+            TVGImage::load_data(&mut BufReader::new(File::open(&path)?))?
+        } else { TVGImage::from_usvg(&fs::read(&path)?)? };
+        let refi = tiny_skia::Pixmap::load_png(path.with_extension("png"))?;
+
+        for (backend, img) in render_backends(&tvg, &RenderOptions::default()) {
+            let (img, stem) = (img?, path.file_stem().unwrap().to_string_lossy());
+            diff_image(&img, &refi)?.save_png(images_dir()?.join(format!("{stem}_{}_diff.png",
+                backend.replace('/', "-"))))?;
+            let diff = compare(&img, &refi)?;
+            assert!(diff.within(psnr, ssim), "{name} by {backend}: {diff}");
+        }
+    }
+
+    let img = tiny_skia::Pixmap::load_png("data/shield-8.png")?;
+    let diff = compare(&img, &img)?;
+    assert!(diff.max == 0 && diff.pixels == 0 && diff.psnr.is_infinite() && diff.ssim == 1.0);
+    assert!(compare(&img, &tiny_skia::Pixmap::load_png("data/gradients.png")?).is_err());
+    Ok(())
+}
+
+#[test] fn arc_sweep_reference() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGImage, render::RenderOptions, compare::*};
+    use std::{fs::File, io::BufReader};
+    // lower halves of a circle and an ellipse by arcs, the reference drawn as polygons
+    let tvg = TVGImage::load_data(&mut BufReader::new(File::open("data/arc.tvg")?))?;
+    let refi = tiny_skia::Pixmap::load_png("data/arc.png")?;
+    for (backend, img) in render_backends(&tvg, &RenderOptions::default()) {
+        let diff = compare(&img?, &refi)?;
+        assert!(diff.within(40.0, 0.99), "arc.tvg by {backend}: {diff}");
+    }   Ok(())
+}
+
+#[cfg(feature = "fidelity")]
+#[test] fn svg_fidelity_report() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{render::RenderOptions, compare::svg_fidelity, convert::Unsupported::*};
+    let opts = RenderOptions::default();
+    let report = svg_fidelity(&std::fs::read("data/fill-polygon.svg")?, &opts)?;
+    assert!(report.diff.pixels == 0 && report.lost.is_empty());
+
+    let report = svg_fidelity(br##"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64">
+        <defs><clipPath id="clip"><circle cx="16" cy="16" r="12"/></clipPath>
+            <mask id="mask"><rect width="32" height="32" fill="white"/></mask>
+            <filter id="blur"><feGaussianBlur stdDeviation="2"/></filter>
+            <pattern id="dots" width="4" height="4" patternUnits="userSpaceOnUse">
+                <circle cx="2" cy="2" r="1"/></pattern>
+            <linearGradient id="rainbow" gradientUnits="userSpaceOnUse" x2="64"
+                gradientTransform="rotate(45)">
+                <stop offset="0" stop-color="red"/><stop offset="0.5" stop-color="lime"/>
+                <stop offset="1" stop-color="blue"/></linearGradient></defs>
+        <rect width="32" height="32" fill="blue" clip-path="url(#clip)"/>
+        <rect x="32" width="32" height="32" fill="red" mask="url(#mask)"/>
+        <rect y="32" width="32" height="32" fill="green" filter="url(#blur)"/>
+        <g opacity="0.5"><rect x="32" y="32" width="32" height="32" fill="url(#dots)"/></g>
+        <path d="M0 60H64" stroke-width="2" stroke="url(#rainbow)" stroke-dasharray="4 2"/>
+        <image width="8" height="8" href="data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAAB
+            CAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg=="/></svg>"##, &opts)?;
+    assert!(report.lost.keys().copied().eq([ClipPath, Mask, Filter, GroupOpacity, Pattern,
+        MultiStopGradient, GradientTransform, DashArray, RasterImage]));
+    assert!(!report.diff.within(30.0, 0.95));   // too lossy for TinyVG
+    assert!(report.to_string().contains("Unsupported clip-path: 1 times"));
+    report.diff_image.save_png(images_dir()?.join("fidelity_diff.png"))?;
+    Ok(())
+}
+
+/// Export `data/everything.tvg` by `save` into `target/images/everything.{ext}` to check.
+fn export_everything(ext: &str,
+    save: impl Fn(&intvg::tinyvg::TVGImage, &mut Vec<u8>) -> std::io::Result<()>)
+    -> Result<(intvg::tinyvg::TVGImage, Vec<u8>), Box<dyn std::error::Error>> {
+    let file = std::fs::File::open("data/everything.tvg")?;
+    let tvg = intvg::tinyvg::TVGImage::load_data(&mut std::io::BufReader::new(file))?;
+    let mut out = vec![];   save(&tvg, &mut out)?;
+    std::fs::write(images_dir()?.join(format!("everything.{ext}")), &out)?;    Ok((tvg, out))
+}
+
+#[test] fn svg_export_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGBuf, convert::Convert, svg::SvgExport,
+        render::{Render, RenderOptions}, compare::compare};
+    use std::{fs, io::Cursor};
+
+    for entry in fs::read_dir("data")? { let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "svg") { continue }
+        let mut tvg0 = TVGBuf::from_usvg(&fs::read(&path)?)?;
+        let mut svgd = vec![];  tvg0.save_svg(&mut svgd)?;
+        let mut tvg1 = TVGBuf::from_usvg(&svgd)?;
+
+        let names = |tvg: &TVGBuf| { let mut names: Vec<_> =
+            tvg.named_ranges().into_iter().map(|nr| (nr.name, nr.range)).collect();
+            names.sort_by(|a, b| a.0.cmp(&b.0));  names }; // depth may change by transforms
+        assert_eq!(names(&tvg0), names(&tvg1));
+        tvg0.trailer.clear();   tvg1.trailer.clear();
+
+        let (mut bin0, mut bin1) = (Cursor::new(vec![]), Cursor::new(vec![]));
+        tvg0.save_data(&mut bin0)?;     tvg1.save_data(&mut bin1)?;
+        assert!(bin0.get_ref() == bin1.get_ref(), "unstable geometry: {}", path.display());
+    }
+
+    let (tvg, svgd) = export_everything("svg", |tvg, out| tvg.save_svg(out))?;
+    let opts = RenderOptions::default();    // renders as the original after parsed by usvg
+    let diff = compare(&TVGBuf::from_usvg(&svgd)?.render_with(&opts)?, &tvg.render_with(&opts)?)?;
+    assert!(diff.within(55.0, 0.999), "{diff}");
+
+    let svgd = String::from_utf8(svgd)?;
+    for elem in ["<rect ", "<polygon ", "<polyline ", "<path ",
+        "<linearGradient ", "<radialGradient ", "gradientUnits=\"userSpaceOnUse\"",
+        " A", "stroke-linejoin=\"round\""] { assert!(svgd.contains(elem), "missing {elem}") }
+    Ok(())
+}
+
+#[test] fn pdf_export() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGBuf, convert::Convert, pdf::PdfExport};
+    // the xref offsets and stream lengths are exact, and the references are to objects
+    let check_pdf = |pdf: &[u8]| -> Result<String, Box<dyn std::error::Error>> {
+        let text = String::from_utf8_lossy(pdf).into_owned();
+        let xref: usize = text.rsplit("startxref\n").next().and_then(|tail|
+            tail.lines().next()).ok_or("no startxref")?.parse()?;
+        assert!(pdf.starts_with(b"%PDF-1.4\n") && pdf[xref..].starts_with(b"xref\n"));
+        let mut count = 0;
+        for entry in std::str::from_utf8(&pdf[xref..])?.lines().skip(3)
+            .take_while(|line| line.ends_with(" n ")) {     count += 1;
+            let offset: usize = entry[..10].parse()?;
+            assert!(pdf[offset..].starts_with(format!("{count} 0 obj\n").as_bytes()));
+        }
+        assert!(text.contains(&format!("trailer\n<< /Size {} ", count + 1)) &&
+            text.matches(" 0 obj\n").count() == count && text.matches("endobj").count() == count);
+
+        for pos in (0..pdf.len()).filter(|&pos| pdf[pos..].starts_with(b" >>\nstream\n")) {
+            let length: usize = String::from_utf8_lossy(&pdf[..pos]).rsplit("/Length ")
+                .next().ok_or("no /Length")?.parse()?;  // in bytes, text is lossy
+            assert!(pdf[pos + 11 + length..].starts_with(b"endstream"), "wrong /Length {length}");
+        }
+        for (pos, _) in text.match_indices(" 0 R") {
+            let obj: usize = text[..pos].rsplit(|c: char| !c.is_ascii_digit()).next()
+                .ok_or("no reference")?.parse()?;
+            assert!(0 < obj && obj <= count, "dangling reference {obj}");
+        }   Ok(text)
+    };
+
+    let (_, pdf) = export_everything("pdf", |tvg, out| tvg.save_pdf(out))?;
+    let text = check_pdf(&pdf)?;
+    assert!(text.contains("/MediaBox [0 0 400 768]") && text.contains("1 J 1 j"));
+    for op in [" re\n", " m\n", " l\n", " c\n", "h\n", "f Q", "S Q", " rg", " RG",
+        "/ShadingType 2", "/ShadingType 3", "/FunctionType 3", "/Pattern cs"] {
+        assert!(text.contains(op), "missing {op}") }
+
+    let tvg = TVGBuf::from_usvg(br#"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="32">
+        <linearGradient id="fade" gradientUnits="userSpaceOnUse" x2="32">
+            <stop offset="0" stop-color="red"/><stop offset="1" stop-color="red"
+            stop-opacity="0"/></linearGradient>
+        <rect width="32" height="32" fill="url(#fade)"/>
+        <circle cx="48" cy="16" r="12" fill="blue" fill-opacity="0.5"/></svg>"#)?;
+    let mut pdf = vec![];   tvg.save_pdf(&mut pdf)?;    let text = check_pdf(&pdf)?;
+    assert!(text.contains("/ca 0.502 /CA 0.502") && text.contains("/S /Luminosity") &&
+        text.contains("/GS0 gs") && text.contains("/GS1 gs"));
+    Ok(())
+}
+
+#[test] fn eps_export() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{tinyvg::TVGBuf, convert::Convert, eps::EpsExport};
+    // the DSC comments are in order, the bounding box in the page, and the program
+    // balanced of only operators known or defined in the prolog
+    let check_eps = |text: &str, size: (f32, f32)| -> Result<(), Box<dyn std::error::Error>> {
+        let dsc = ["%!PS-Adobe-3.0 EPSF-3.0\n", "%%BoundingBox: ", "%%HiResBoundingBox: ",
+            "%%EndComments\n", "%%BeginProlog\n", "%%EndProlog\n", "showpage\n%%EOF\n"];
+        let pos = dsc.iter().map(|c| text.find(c).ok_or(format!("missing {c}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(pos[0] == 0 && pos.is_sorted() && text.ends_with(dsc[6]));
+
+        let bbox = |dsc: &str| -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+            Ok(text[text.find(dsc).unwrap() + dsc.len()..].lines().next().unwrap()
+                .split(' ').map(str::parse).collect::<Result<_, _>>()?) };
+        let (bbox, hires) = (bbox(dsc[1])?, bbox(dsc[2])?);
+        assert!(0.0 <= bbox[0] && bbox[0] < bbox[2] && bbox[2] <= size.0.ceil() &&
+                0.0 <= bbox[1] && bbox[1] < bbox[3] && bbox[3] <= size.1.ceil());
+        assert!(bbox[0] <= hires[0] && bbox[1] <= hires[1] &&
+            hires[2] <= bbox[2] && hires[3] <= bbox[3]);
+
+        let mut known = ["moveto", "lineto", "curveto", "closepath", "rlineto", "roll", "index",
+            "exch", "neg", "def", "bind", "load", "matrix", "currentmatrix", "setmatrix",
+            "translate", "rotate", "scale", "arc", "arcn", "ifelse", "true", "false", "gsave",
+            "grestore", "rectclip", "setlinecap", "setlinejoin", "setlinewidth", "setrgbcolor",
+            "newpath", "fill", "stroke", "strokepath", "clip", "shfill", "showpage"].to_vec();
+        let (mut depth, mut gsaves) = (vec![], 0);
+        let program = text[pos[3]..].lines().filter(|line| !line.starts_with('%'))
+            .collect::<Vec<_>>().join(" ");
+        let program = ["[", "]", "{", "}", "<<", ">>"].iter().fold(program, |prog, delim|
+            prog.replace(delim, &format!(" {delim} ")));
+        for token in program.split_whitespace() {
+            match token {
+                "{" | "[" | "<<" => depth.push(token),
+                "}" | "]" | ">>" => assert_eq!(depth.pop(),
+                    Some(match token { "}" => "{", "]" => "[", _ => "<<" })),
+                "gsave" => gsaves += 1,
+                "grestore" => { gsaves -= 1;    assert!(0 <= gsaves) }
+                _ if token.parse::<f32>().is_ok() || known.contains(&token) => (),
+                _ => { let name = token.strip_prefix('/').ok_or(format!("unknown {token}"))?;
+                    known.push(name) }
+            }
+        }   assert!(depth.is_empty() && gsaves == 0);   Ok(())
+    };
+
+    let (tvg, eps) = export_everything("eps", |tvg, out| tvg.save_eps(out))?;
+    let text = String::from_utf8(eps)?;
+    check_eps(&text, (tvg.header.width as _, tvg.header.height as _))?;
+    for op in ["%%LanguageLevel: 3", "1 setlinecap 1 setlinejoin", " re\n", " c\n", " arc\n",
+        " ea\n", "3.5 setlinewidth", "1.5 setlinewidth", "/ShadingType 2", "/ShadingType 3",
+        "clip Sh0 shfill", "strokepath clip"] { assert!(text.contains(op), "missing {op}") }
+
+    let tvg = TVGBuf::from_usvg(br#"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="32">
+        <rect x="8" y="4" width="16" height="8" fill-opacity="0.5"/>
+        <path d="M40 4H56" stroke="red" stroke-width="2"/></svg>"#)?;
+    let mut eps = vec![];   tvg.save_eps(&mut eps)?;    let text = String::from_utf8(eps)?;
+    check_eps(&text, (64.0, 32.0))?;
+    assert!(text.contains("%%BoundingBox: 8 20 57 29\n%%HiResBoundingBox: 8 20 57 29\n"));
+    assert!(text.contains("gsave 0.498 0.498 0.498 setrgbcolor"));  // composed over white
+    Ok(())
+}
+
+mod everything { include!("codegen/everything.rs"); }
+
+#[test] fn codegen() -> Result<(), Box<dyn std::error::Error>> {
+    use intvg::{render::{Render, RenderOptions}, codegen::{CodeGen, Lang, identifier}};
+    let (tvg, code) = export_everything("rs", |tvg, out|
+        tvg.save_code(Lang::Rust, "everything", out))?;
+    assert!(code == std::fs::read("tests/codegen/everything.rs")?, "stale generated code, \
+        regenerate by `intvg data/everything.tvg tests/codegen/everything.rs`");
+
+    for scale in [1.0, 0.5, 2.0] {  // the generated renders the same as interpreted
+        let opts = RenderOptions { scale, ..Default::default() };
+        let (width, height, trfm) = opts.layout(everything::EVERYTHING_SIZE.0,
+            everything::EVERYTHING_SIZE.1);
+        let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or("Fail to create pixmap")?;
+        everything::draw_everything(&mut pixmap.as_mut(), trfm);
+        assert!(pixmap.data() == tvg.render_with(&opts)?.data(), "differs at scale {scale}");
+    }
+
+    for (lang, decls) in [(Lang::JavaScript, ["const EVERYTHING_COLORS = [\n    \"#e7a915\",",
+        "function draw_everything(ctx) {", "createRadialGradient(325.0, 50.0, 0, 325.0, 50.0, "]),
+        (Lang::C, ["static const uint32_t EVERYTHING_COLORS[6] = {\n    0xe7a915ff,",
+        "void draw_everything(void *ctx) {", "{ TVG_RADIAL, 325.0, 50.0, 375.0, 75.0, "])] {
+        let mut code = vec![];  tvg.save_code(lang, "everything", &mut code)?;
+        let code = String::from_utf8(code)?;
+        for decl in decls { assert!(code.contains(decl), "missing {decl}") }
+    }
+
+    for (name, ident) in [("8bit", "tvg_8bit"), ("app-icon", "app_icon"), ("", "tvg_")] {
+        assert_eq!(identifier(name), ident);
+        let err = tvg.save_code(Lang::C, name, &mut vec![]).err().map(|err| err.kind());
+        assert_eq!(err, (name != ident).then_some(std::io::ErrorKind::InvalidInput));
+    }   Ok(())
+}
@@ -65,3 +65,36 @@
     Ok(())
 }
 
+
+#[test] fn grays_demo() {
+    use intvg::{grays::*, tinyvg::Point};
+    let points = [ // FTDemo_Icon @freetype2/demo/src/ftcommon.c, y up in 64x64 pixels
+        ( 4, 8), ( 4,10), ( 8,12), ( 8,52), ( 4,54), ( 4,56), (60,56), (60,44), (58,44),
+        (56,52), (44,52), (44,12), (48,10), (48, 8), (32, 8), (32,10), (36,12), (36,52),
+        (16,52), (16,36), (24,36), (26,40), (28,40), (28,28), (26,28), (24,32), (16,32),
+        (16,12), (20,10), (20, 8), ].map(|(x, y)| Point { x: x as _, y: (64 - y) as _ });
+
+    let mut raster = Rasterizer::new(64, 64);
+    let mut bitmap = vec![0u8; 64 * 64];
+    raster.move_to(points[0]);  points[1..].iter().for_each(|pt| raster.line_to(*pt));
+    raster.render(FillRule::NonZero, |y, spans| for span in spans {  // gray spans
+        bitmap[y as usize * 64..][span.x as usize..][..span.len as usize].fill(span.coverage);
+    });
+
+    let at = |x: usize, y: usize| bitmap[(64 - y) * 64 + x];
+    assert!(at(10, 30) == 255 && at(40, 30) == 255 && at(50, 54) == 255);  // stems and bar
+    assert!(at(2, 30) == 0 && at(30, 30) == 0 && at(50, 30) == 0);
+    assert!(0 < at(57, 48) && at(57, 48) < 255);    // anti-aliased slope of the serif
+
+    for (rule, hole) in [(FillRule::NonZero, 255), (FillRule::EvenOdd, 0)] {
+        let mut inner = 0;  // a square within another square of the same direction
+        for (x0, x1) in [(0.0, 16.0), (4.0, 12.0)] {
+            raster.move_to(Point { x: x0, y: x0 });     raster.line_to(Point { x: x1, y: x0 });
+            raster.line_to(Point { x: x1, y: x1 });     raster.line_to(Point { x: x0, y: x1 });
+        }
+        raster.render(rule, |y, spans| if y == 8 {
+            inner = spans.iter().find(|span| (span.x..span.x + span.len as i32)
+                .contains(&8)).map_or(0, |span| span.coverage);
+        }); assert!(inner == hole);
+    }
+}
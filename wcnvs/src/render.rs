@@ -5,7 +5,8 @@
  * Copyright (c) 2024 M.H.Fan, All rights reserved.             *
  ****************************************************************/
 
-use {std::io, intvg::tinyvg::*};
+use {std::{io, convert::Infallible}, intvg::{tinyvg::*, canvas::{Canvas, svg_arc},
+    render::{RenderOptions, gradient_stops, min_line_width}}};
 use web_sys::{CanvasRenderingContext2d as Context2d, Path2d};
 
 pub fn render_svg(tree: &usvg::Tree, ctx2d: &Context2d, cw: u32, ch: u32) {
@@ -140,203 +141,103 @@ fn convert_paint(ctx2d: &Context2d, paint: &usvg::Paint,
 }
 
 pub fn render_tvg<R: io::Read, W: io::Write>(tvg: &TinyVG<R, W>,
-    ctx2d: &Context2d, cw: u32, ch: u32) {
-    let (tw, th) = (tvg.header.width as f64, tvg.header.height as f64);
-    let scale = (cw as f64 / tw).min(ch as f64 / th);
+    ctx2d: &Context2d, cw: u32, ch: u32) {  // contain & centered
+    render_tvg_with(tvg, ctx2d, &RenderOptions { size: Some((cw, ch)), ..Default::default() })
+}
+
+/// Render with the layout (size, fit, align, transform, region), `background`, `layers`,
+/// `commands` and `srgb_gradient` options, the same as the native renderers.
+/// XXX: Canvas2D always anti-aliases paths, the quality options (`anti_alias`,
+/// `supersample`, `filter`, `gamma_correct`), `threads` and `debug` don't apply here.
+pub fn render_tvg_with<R: io::Read, W: io::Write>(tvg: &TinyVG<R, W>,
+    ctx2d: &Context2d, opts: &RenderOptions) {
+    let (cw, ch, trfm) = opts.layout(tvg.header.width, tvg.header.height);
+    let hairline = min_line_width(&trfm);
 
     ctx2d.reset();  //ctx2d.clear_rect(0.0, 0.0, cw as _, ch as _);
-    let _ = ctx2d.translate((cw as f64 - scale * tw) / 2., (ch as f64 - scale * th) / 2.);
-    let _ = ctx2d.scale(scale, scale);  ctx2d.set_line_join("round");
+    if let Some(color) = opts.background {
+        ctx2d.set_fill_style_str(&to_css_color(color));
+        ctx2d.fill_rect(0.0, 0.0, cw as _, ch as _);
+    }
+    let _ = ctx2d.set_transform(trfm.sx as _, trfm.ky as _, trfm.kx as _,
+        trfm.sy as _, trfm.tx as _, trfm.ty as _);  ctx2d.set_line_join("round");
     ctx2d.set_miter_limit(4.0);         ctx2d.set_line_cap ("round");
 
-    for cmd in &tvg.commands {
-        let mut path = Path2d::new().unwrap();
-        match cmd { Command::EndOfDocument => (),
-
-            Command::FillPolyg(FillCMD { fill, coll }) => {
-                let mut iter = coll.iter();
-                if let Some(pt) = iter.next() { path.move_to(pt.x as _, pt.y as _) }
-                iter.for_each(|pt|   path.line_to(pt.x as _, pt.y as _));
-                ctx2d.set_fill_style_str(&convert_style(tvg, ctx2d, fill)); path.close_path();
-                ctx2d.fill_with_path_2d(&path);
-            }
-            Command::FillRects(FillCMD { fill, coll }) => {
-                ctx2d.set_fill_style_str(&convert_style(tvg, ctx2d, fill));
-                coll.iter().for_each(|rect| {
-                    path.rect(rect.x as _, rect.y as _, rect.w as _, rect.h as _);
-                }); ctx2d.fill_with_path_2d(&path);
-            }
-            Command::FillPath (FillCMD { fill, coll }) => {
-                ctx2d.set_fill_style_str(&convert_style(tvg, ctx2d, fill));
-                for seg in coll {   let _ = segment_to_path(seg, &path);
-                    //if res { return Err("Got line width in fill path segment") }
-                }   ctx2d.fill_with_path_2d(&path);
-            }
-            Command::DrawLines(DrawCMD { line, lwidth, coll }) => {
-                ctx2d.set_stroke_style_str(&convert_style(tvg, ctx2d, line));
-                ctx2d.set_line_width(*lwidth as _);
-                coll.iter().for_each(|line| {
-                    path.move_to(line.start.x as _, line.start.y as _);
-                    path.line_to(line.  end.x as _, line.  end.y as _);
-                }); ctx2d.stroke_with_path(&path);
-            }
-            Command::DrawLoop (DrawCMD { line, lwidth, coll },
-                strip) => {     let mut iter = coll.iter();
-                if let Some(pt) = iter.next() { path.move_to(pt.x as _, pt.y as _) }
-                iter.for_each(|pt| path.line_to(pt.x as _, pt.y as _));
-
-                ctx2d.set_line_width(*lwidth as _);     if !*strip { path.close_path(); }
-                ctx2d.set_stroke_style_str(&convert_style(tvg, ctx2d, line));
-                ctx2d.stroke_with_path(&path);
-            }
-            Command::DrawPath (DrawCMD {
-                line, lwidth, coll }) => {
-                ctx2d.set_line_width(*lwidth as _);
-                ctx2d.set_stroke_style_str(&convert_style(tvg, ctx2d, line));
-                for seg in coll { stroke_segment_path(seg, ctx2d); }
-            }
-            Command::OutlinePolyg(fill, DrawCMD {
-                line, lwidth, coll }) => {
-                let mut iter = coll.iter();
-                if let Some(pt) = iter.next() { path.move_to(pt.x as _, pt.y as _) }
-                iter.for_each(|pt| path.line_to(pt.x as _, pt.y as _));
-
-                ctx2d.set_line_width(*lwidth as _);     path.close_path();
-                ctx2d.set_fill_style_str  (&convert_style(tvg, ctx2d, fill));
-                ctx2d.set_stroke_style_str(&convert_style(tvg, ctx2d, line));
-                ctx2d.fill_with_path_2d(&path);     ctx2d.stroke_with_path (&path);
-            }
-            Command::OutlineRects(fill, DrawCMD {
-                line, lwidth, coll }) => {
-                ctx2d.set_fill_style_str  (&convert_style(tvg, ctx2d, fill));
-                ctx2d.set_stroke_style_str(&convert_style(tvg, ctx2d, line));
-                ctx2d.set_line_width(*lwidth as _);
-
-                coll.iter().for_each(|rect| {
-                    path.rect(rect.x as _, rect.y as _, rect.w as _, rect.h as _);
-                }); ctx2d.fill_with_path_2d(&path);
-                    ctx2d.stroke_with_path (&path);
-            }
-            Command::OutlinePath (fill, DrawCMD {
-                line, lwidth, coll }) => {
-                ctx2d.set_fill_style_str  (&convert_style(tvg, ctx2d, fill));
-                ctx2d.set_stroke_style_str(&convert_style(tvg, ctx2d, line));
-                ctx2d.set_line_width(*lwidth as _);
-
-                for seg in coll {   let res = segment_to_path(seg, &path);
-                    ctx2d.fill_with_path_2d(&path);
-
-                    if res { stroke_segment_path(seg, ctx2d);
-                    } else { ctx2d.stroke_with_path(&path); }
-                    path = Path2d::new().unwrap();
-                }
-            }
-        }
+    let mut canvas = Ctx2dCanvas { ctx2d, path: Path2d::new().unwrap(), hairline,
+        srgb: opts.srgb_gradient };
+    for (cmd, show) in tvg.commands.iter().zip(opts.layer_mask(tvg)) {
+        if show { let _ = canvas.draw_command(tvg, cmd); }
     }
 }
 
-fn stroke_segment_path(seg: &Segment, ctx2d: &Context2d) {
-    let mut path = Path2d::new().unwrap();
-    path.move_to(seg.start.x as _, seg.start.y as _);
-
-    let mut last_point = seg.start;
-    for cmd in &seg.cmds {
-        if let Some(width) = cmd.lwidth {
-            if true/* 1 < path.get_size()*/ { // XXX:
-                ctx2d.stroke_with_path(&path);
-
-                path = Path2d::new().unwrap();
-                path.move_to(last_point.x as _, last_point.y as _);
-            }   ctx2d.set_line_width(width as _);
-        }   process_segcmd(&path, &cmd.instr, &mut last_point);
-    }           ctx2d.stroke_with_path(&path);
-}
-
-fn segment_to_path(seg: &Segment, path: &Path2d) -> bool {
-    path.move_to(seg.start.x as _, seg.start.y as _);
-    let mut change_lw = false;
-
-    let mut last_point = seg.start;
-    for cmd in &seg.cmds {
-        if cmd.lwidth.is_some() { change_lw = true }
-        process_segcmd(path, &cmd.instr, &mut last_point);
-    }   change_lw
-}
-
-fn process_segcmd(path: &Path2d, cmd: &SegInstr, last_point: &mut Point) {
-    match cmd {     SegInstr::ClosePath => path.close_path(),
-        SegInstr::Line  { end } => {
-            path.line_to(end.x as _, end.y as _);      *last_point = *end;
-        }
-        SegInstr::HLine { x }     => {
-            path.line_to(*x as _, last_point.y as _);   last_point.x = *x;
-        }
-        SegInstr::VLine { y }     => {
-            path.line_to(last_point.x as _, *y as _);   last_point.y = *y;
-        }
+struct Ctx2dCanvas<'a> { ctx2d: &'a Context2d, path: Path2d, hairline: f32, srgb: bool }
 
-        SegInstr::CubicBezier { ctrl, end } => {
-            path.bezier_curve_to(ctrl.0.x as _, ctrl.0.y as _, ctrl.1.x as _, ctrl.1.y as _,
-                end.x as _, end.y as _);    *last_point = *end;
-        }
+impl Canvas for Ctx2dCanvas<'_> {
+    type Paint = String;    type Error = Infallible;
 
-        SegInstr::ArcCircle  { large, sweep, radius, end } => {
-            wcns_arc_to(path, last_point, &(*radius, *radius), 0.0,
-                *large, *sweep, end);   *last_point = *end;
-        }
+    fn paint<R: io::Read, W: io::Write>(&mut self, img: &TinyVG<R, W>,
+        style: &Style) -> Result<Self::Paint, Self::Error> {
+        Ok(convert_style(img, self.ctx2d, style, self.srgb))
+    }
 
-        SegInstr::ArcEllipse { large, sweep, radii,
-            rotation, end } => {
-            wcns_arc_to(path, last_point, radii, *rotation,
-                *large, *sweep, end);   *last_point = *end;
-        }
+    fn move_to (&mut self, pt: Point) { self.path.move_to(pt.x as _, pt.y as _) }
+    fn line_to (&mut self, pt: Point) { self.path.line_to(pt.x as _, pt.y as _) }
+    fn quad_to (&mut self, ctrl: Point, end: Point) {
+        self.path.quadratic_curve_to(ctrl.x as _, ctrl.y as _, end.x as _, end.y as _)
+    }
+    fn cubic_to(&mut self, ctrl: (Point, Point), end: Point) {
+        self.path.bezier_curve_to(ctrl.0.x as _, ctrl.0.y as _, ctrl.1.x as _, ctrl.1.y as _,
+            end.x as _, end.y as _)
+    }
+    fn arc_to(&mut self, start: Point, radii: (f32, f32), rotation: f32,
+        large: bool, sweep: bool, end: Point) -> Result<(), Self::Error> {
+        match kurbo::Arc::from_svg_arc(&svg_arc(start, radii, rotation, large, sweep, end)) {
+            None => self.line_to(end),
+            Some(arc) => arc.to_cubic_beziers(0.1, |p1, p2, p| self.path.bezier_curve_to(
+                p1.x as _, p1.y as _, p2.x as _, p2.y as _, p.x as _, p.y as _)),
+        }   Ok(())
+    }
+    fn close(&mut self) { self.path.close_path() }
+    fn rect (&mut self, rect: &Rect) -> Result<(), Self::Error> {
+        self.path.rect(rect.x as _, rect.y as _, rect.w as _, rect.h as _);     Ok(())
+    }
 
-        SegInstr::QuadBezier { ctrl, end } => {
-            path.quadratic_curve_to(ctrl.x as _, ctrl.y as _,
-                end.x as _, end.y as _);    *last_point = *end;
-        }
+    fn fill  (&mut self, paint: &Self::Paint) -> Result<(), Self::Error> {
+        self.ctx2d.set_fill_style_str(paint);   self.ctx2d.fill_with_path_2d(&self.path); Ok(())
+    }
+    fn stroke(&mut self, paint: &Self::Paint, width: f32) -> Result<(), Self::Error> {
+        self.ctx2d.set_stroke_style_str(paint);
+        self.ctx2d.set_line_width(width.max(self.hairline) as _);
+        self.ctx2d.stroke_with_path(&self.path);    Ok(())
     }
+    fn clear (&mut self) { self.path = Path2d::new().unwrap() }
 }
 
-fn wcns_arc_to(path: &Path2d, start: &Point, radii: &(f32, f32),
-    rotation: f32, large: bool, sweep: bool, end: &Point) {
-    let svg_arc = kurbo::SvgArc {
-           to: kurbo::Point::new(end.x as _, end.y as _),
-         from: kurbo::Point::new(start.x as _, start.y as _),
-        radii: kurbo::Vec2 ::new(radii.0 as _, radii.1 as _),
-        x_rotation: (rotation as f64).to_radians(), large_arc: large, sweep,
-    };
-
-    match kurbo::Arc::from_svg_arc(&svg_arc) {
-        None => path.line_to(end.x as _, end.y as _),
-        Some(arc) => arc.to_cubic_beziers(0.1, |p1, p2, p| path.bezier_curve_to(
-            p1.x as _, p1.y as _, p2.x as _, p2.y as _, p.x as _, p.y as _)),
-    }
+fn to_css_color(color: RGBA8888) -> String {
+    let mut str = format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b);
+    if color.a != 255 {   str.push_str(&format!("{:02x}", color.a)); }  str
 }
 
 fn convert_style<R: io::Read, W: io::Write>(img: &TinyVG<R, W>,
-    ctx2d: &Context2d, style: &Style) -> String {
-    fn to_css_color<R: io::Read, W: io::Write>(img: &TinyVG<R, W>, idx: u32) -> String {
-        let color = img.lookup_color(idx);
-        let mut str = format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b);
-        if color.a != 255 {   str.push_str(&format!("{:02x}", color.a)); }  str
-    }
+    ctx2d: &Context2d, style: &Style, srgb: bool) -> String {
+    let stops = |cindex: &(u32, u32)| gradient_stops(img.lookup_color(cindex.0),
+        img.lookup_color(cindex.1), srgb);
 
-    match style {   Style::FlatColor(idx) => to_css_color(img, *idx),
+    match style {   Style::FlatColor(idx) => to_css_color(img.lookup_color(*idx)),
 
         Style::LinearGradient { points, cindex } => {
             let linear = ctx2d.create_linear_gradient(
                 points.0.x as _, points.0.y as _, points.1.x as _, points.1.y as _);
-            let _ = linear.add_color_stop(0.0, &to_css_color(img, cindex.0));
-            let _ = linear.add_color_stop(1.0, &to_css_color(img, cindex.1));
+            for (pos, color) in stops(cindex) {
+                let _ = linear.add_color_stop(pos, &to_css_color(color)); }
             linear.as_string().unwrap_or("".to_owned())
         }   // don't need to scale, since created in context
         Style::RadialGradient { points, cindex } => {
             let radial = ctx2d.create_radial_gradient(  // XXX: 0.
                 points.0.x as _, points.0.y as _, 1., points.0.x as _, points.0.y as _,
                (points.1.x - points.0.x).hypot(points.1.y - points.0.y) as _).unwrap();
-            let _ = radial.add_color_stop(0.0, &to_css_color(img, cindex.0));
-            let _ = radial.add_color_stop(1.0, &to_css_color(img, cindex.1));
+            for (pos, color) in stops(cindex) {
+                let _ = radial.add_color_stop(pos, &to_css_color(color)); }
             radial.as_string().unwrap_or("".to_owned())
         }
     }